@@ -39,6 +39,18 @@ pub struct Args {
     pub config_path: Option<PathBuf>,
     #[arg(global = true, short = 'd', long = "dir", help = "Path to workspace directory", value_parser = parse_absolute_path)]
     pub workspace_dir: Option<PathBuf>,
+    #[arg(
+        global = true,
+        long = "no-cache",
+        help = "Do not use cached dependency command results"
+    )]
+    pub no_cache: bool,
+    #[arg(
+        global = true,
+        long = "lenient-config",
+        help = "Warn about unknown configuration keys instead of failing"
+    )]
+    pub lenient_config: bool,
     #[command(subcommand)]
     pub subcommand: Subcommand,
 }
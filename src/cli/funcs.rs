@@ -21,6 +21,7 @@
 //
 use path_absolutize::Absolutize;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 pub fn parse_absolute_path(s: &str) -> Result<PathBuf, String> {
     Path::new(s)
@@ -28,3 +29,44 @@ pub fn parse_absolute_path(s: &str) -> Result<PathBuf, String> {
         .map_err(|_| String::from("invalid path"))
         .map(|x| x.to_path_buf())
 }
+
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let i = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+    let (value_str, unit) = s.split_at(i);
+    let value = value_str
+        .parse::<f64>()
+        .map_err(|_| format!("invalid duration {s}"))?;
+    let scale = match unit {
+        "ms" => 0.001,
+        "" | "s" => 1.0,
+        "m" => 60.0,
+        "h" => 3600.0,
+        _ => return Err(format!("invalid duration unit {unit}")),
+    };
+    Duration::try_from_secs_f64(value * scale).map_err(|_| format!("invalid duration {s}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_duration;
+    use rstest::rstest;
+    use std::time::Duration;
+
+    #[rstest]
+    #[case(Ok(Duration::from_secs(10)), "10")]
+    #[case(Ok(Duration::from_secs(10)), "10s")]
+    #[case(Ok(Duration::from_millis(1500)), "1.5s")]
+    #[case(Ok(Duration::from_millis(250)), "250ms")]
+    #[case(Ok(Duration::from_secs(90)), "1.5m")]
+    #[case(Ok(Duration::from_secs(36)), "0.01h")]
+    #[case(Err(String::from("invalid duration unit d")), "1d")]
+    #[case(Err(String::from("invalid duration s")), "s")]
+    fn parse_duration_basics(
+        #[case] expected_result: Result<Duration, String>,
+        #[case] input: &str,
+    ) {
+        assert_eq!(expected_result, parse_duration(input));
+    }
+}
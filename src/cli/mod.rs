@@ -22,9 +22,20 @@
 mod args;
 mod constants;
 mod funcs;
+mod graph_format;
+mod keep_going;
+mod output_mode;
 mod project_order;
+mod report_format;
 mod subcommand;
 
 pub use self::args::Args;
+pub use self::graph_format::GraphFormat;
+pub use self::keep_going::KeepGoing;
+pub use self::output_mode::OutputMode;
 pub use self::project_order::ProjectOrder;
-pub use self::subcommand::{ShellCommandInfo, Subcommand};
+pub use self::report_format::ReportFormat;
+pub use self::subcommand::{
+    CacheInfo, CacheSubcommand, ConfigInfo, ConfigSubcommand, GraphInfo, ProjectQueryInfo,
+    ShellCommandInfo, Subcommand, WhyInfo,
+};
@@ -20,11 +20,15 @@
 // CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 //
 use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, ValueEnum)]
+#[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Serialize, ValueEnum)]
 pub enum ProjectOrder {
     #[clap(name = "alpha")]
+    #[serde(rename = "alpha")]
     Alphabetical,
     #[clap(name = "topo")]
+    #[serde(rename = "topo")]
     Topological,
 }
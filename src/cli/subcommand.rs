@@ -19,17 +19,46 @@
 // IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
 // CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 //
+use super::funcs::{parse_absolute_path, parse_duration};
+use super::graph_format::GraphFormat;
+use super::keep_going::KeepGoing;
+use super::output_mode::OutputMode;
 use super::project_order::ProjectOrder;
+use super::report_format::ReportFormat;
 use clap::{Args, Subcommand as ClapSubcommand};
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(ClapSubcommand, Debug)]
 pub enum Subcommand {
+    #[command(name = "cache", about = "Manage cached dependency command results")]
+    Cache(CacheInfo),
+
+    #[command(
+        name = "check",
+        about = "Check workspace configuration and dependency graph"
+    )]
+    Check,
+
+    #[command(
+        name = "config",
+        about = "Validate configuration file or print its schema"
+    )]
+    Config(ConfigInfo),
+
+    #[command(name = "deps", about = "Print projects that given project depends on")]
+    Deps(ProjectQueryInfo),
+
     #[command(
         name = "git",
         about = "Run Git command in each project directory using system Git command"
     )]
     Git(ShellCommandInfo),
 
+    #[command(name = "graph", about = "Print workspace dependency graph")]
+    Graph(GraphInfo),
+
     #[command(name = "info", about = "Print workspace and environment information")]
     Info,
 
@@ -39,23 +68,231 @@ pub enum Subcommand {
     #[command(name = "new", about = "Create new workspace")]
     New,
 
+    #[command(name = "rdeps", about = "Print projects that depend on given project")]
+    Rdeps(ProjectQueryInfo),
+
     #[command(name = "run", about = "Run command in each project directory")]
     Run(ShellCommandInfo),
+
+    #[command(
+        name = "why",
+        about = "Print shortest dependency path from one project to another"
+    )]
+    Why(WhyInfo),
+}
+
+#[derive(Args, Debug)]
+pub struct CacheInfo {
+    #[command(subcommand)]
+    pub cache_subcommand: CacheSubcommand,
+}
+
+#[derive(ClapSubcommand, Debug)]
+pub enum CacheSubcommand {
+    #[command(name = "clear", about = "Delete cached dependency command results")]
+    Clear,
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigInfo {
+    #[command(subcommand)]
+    pub config_subcommand: ConfigSubcommand,
+}
+
+#[derive(ClapSubcommand, Debug)]
+pub enum ConfigSubcommand {
+    #[command(name = "validate", about = "Check configuration file for errors")]
+    Validate,
+
+    #[command(name = "schema", about = "Print JSON Schema for configuration file")]
+    Schema,
+}
+
+#[derive(Args, Debug)]
+pub struct GraphInfo {
+    #[arg(
+        help = "Graph format",
+        long = "format",
+        value_enum,
+        default_value = "dot"
+    )]
+    pub graph_format: GraphFormat,
+
+    #[arg(
+        help = "Highlight given project and its dependencies and dependents",
+        long = "highlight",
+        value_name = "PROJECT"
+    )]
+    pub highlight: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ProjectQueryInfo {
+    #[arg(help = "Project name")]
+    pub project: String,
+
+    #[arg(help = "Include transitive dependencies", long = "transitive")]
+    pub transitive: bool,
 }
 
 #[derive(Args, Debug)]
+pub struct WhyInfo {
+    #[arg(help = "Name of depending project")]
+    pub from_project: String,
+
+    #[arg(help = "Name of project depended on")]
+    pub to_project: String,
+
+    #[arg(
+        help = "Show all projects on any dependency path instead of shortest path",
+        long = "transitive"
+    )]
+    pub transitive: bool,
+}
+
+#[derive(Args, Debug)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct ShellCommandInfo {
     #[arg(help = "Fail fast", short = 'f', long = "fail-fast")]
     pub fail_fast: bool,
 
     #[arg(
-        help = "Project traversal order",
+        help = "Keep going after failures: run all remaining projects or skip dependents of failed projects",
+        long = "keep-going",
+        value_name = "MODE",
+        value_enum,
+        conflicts_with = "fail_fast"
+    )]
+    pub keep_going: Option<KeepGoing>,
+
+    #[arg(
+        help = "Maximum number of projects to run concurrently",
+        short = 'j',
+        long = "jobs",
+        default_value = "1"
+    )]
+    pub jobs: NonZeroUsize,
+
+    #[arg(
+        help = "Only include projects matching glob, or regular expression prefixed with \"re:\"",
+        long = "project",
+        value_name = "PATTERN"
+    )]
+    pub project_patterns: Vec<String>,
+
+    #[arg(
+        help = "Exclude projects matching glob",
+        long = "exclude",
+        value_name = "PATTERN"
+    )]
+    pub exclude_patterns: Vec<String>,
+
+    #[arg(
+        help = "Only include given project and projects it transitively depends on",
+        long = "with-deps",
+        value_name = "PROJECT"
+    )]
+    pub with_deps: Vec<String>,
+
+    #[arg(
+        help = "Only include given project and projects transitively depending on it",
+        long = "with-dependents",
+        value_name = "PROJECT"
+    )]
+    pub with_dependents: Vec<String>,
+
+    #[arg(
+        help = "Only include projects with uncommitted changes or commits not on their upstream branch",
+        long = "changed"
+    )]
+    pub changed: bool,
+
+    #[arg(
+        help = "Only include projects whose HEAD differs from given Git ref (implies --changed)",
+        long = "changed-since",
+        value_name = "REF"
+    )]
+    pub changed_since: Option<String>,
+
+    #[arg(
+        help = "Also include projects transitively depending on changed projects (implies --changed)",
+        long = "changed-dependents"
+    )]
+    pub changed_dependents: bool,
+
+    #[arg(
+        help = "Only run in project directories in which command failed in previous run",
+        long = "only-failed"
+    )]
+    pub only_failed: bool,
+
+    #[arg(
+        help = "Resume traversal at given project",
+        long = "from",
+        value_name = "PROJECT"
+    )]
+    pub from_project: Option<String>,
+
+    #[arg(
+        help = "Project traversal order [default: topo]",
         short = 'o',
         long = "order",
-        default_value_t = ProjectOrder::Topological,
         value_enum
     )]
-    pub project_order: ProjectOrder,
+    pub project_order: Option<ProjectOrder>,
+
+    #[arg(
+        help = "Project output mode",
+        long = "output",
+        default_value_t = OutputMode::Passthrough,
+        value_enum
+    )]
+    pub output_mode: OutputMode,
+
+    #[arg(
+        help = "Directory in which to write per-project log files",
+        long = "log-dir",
+        value_parser = parse_absolute_path
+    )]
+    pub log_dir: Option<PathBuf>,
+
+    #[arg(
+        help = "Path to which to write report",
+        long = "report",
+        value_parser = parse_absolute_path
+    )]
+    pub report: Option<PathBuf>,
+
+    #[arg(
+        help = "Report format",
+        long = "report-format",
+        default_value_t = ReportFormat::Json,
+        value_enum,
+        requires = "report"
+    )]
+    pub report_format: ReportFormat,
+
+    #[arg(
+        help = "Maximum time to allow command to run in each project directory (e.g. 90s, 5m, 1h)",
+        long = "timeout",
+        value_parser = parse_duration
+    )]
+    pub timeout: Option<Duration>,
+
+    #[arg(
+        help = "Number of times to retry failed command in each project directory",
+        long = "retries",
+        default_value_t = 0
+    )]
+    pub retries: usize,
+
+    #[arg(
+        help = "Delay before retrying failed command (e.g. 500ms, 10s)",
+        long = "retry-delay",
+        default_value = "1s",
+        value_parser = parse_duration
+    )]
+    pub retry_delay: Duration,
 
     #[arg(help = "Program or subcommand to run in environment")]
     pub command: String,
@@ -0,0 +1,38 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2020-3 Richard Cook
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy of
+// this software and associated documentation files (the "Software"), to deal in
+// the Software without restriction, including without limitation the rights to
+// use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software is furnished to do so,
+// subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+// IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+// CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+//
+use crate::cli::{CacheInfo, CacheSubcommand};
+use crate::workspace::{DependencyCache, Session};
+use anyhow::Result;
+use colored::Colorize;
+
+pub fn do_cache(session: &Session, cache_info: &CacheInfo) -> Result<()> {
+    match cache_info.cache_subcommand {
+        CacheSubcommand::Clear => {
+            if DependencyCache::clear(&session.state_dir())? {
+                println!("{}", "Cleared dependency cache".green());
+            } else {
+                println!("{}", "Dependency cache is already empty".yellow());
+            }
+        }
+    }
+    Ok(())
+}
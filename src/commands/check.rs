@@ -0,0 +1,45 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2020-3 Richard Cook
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy of
+// this software and associated documentation files (the "Software"), to deal in
+// the Software without restriction, including without limitation the rights to
+// use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software is furnished to do so,
+// subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+// IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+// CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+//
+use crate::shell_runner::ShellResult;
+use crate::workspace::{Plan, Session};
+use colored::Colorize;
+
+pub fn do_check(session: &Session) -> ShellResult {
+    let problems = match Plan::check(session) {
+        Ok((_, problems)) => problems,
+        Err(e) => vec![e.to_string()],
+    };
+
+    if problems.is_empty() {
+        println!("{}", "No problems found".green());
+        return ShellResult::Success;
+    }
+
+    for problem in &problems {
+        println!("{}", format!("Error: {problem}").red());
+    }
+    println!(
+        "{}",
+        format!("Found {} problem(s)", problems.len()).bright_red()
+    );
+    ShellResult::Failure
+}
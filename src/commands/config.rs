@@ -0,0 +1,88 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2020-3 Richard Cook
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy of
+// this software and associated documentation files (the "Software"), to deal in
+// the Software without restriction, including without limitation the rights to
+// use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software is furnished to do so,
+// subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+// IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+// CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+//
+use crate::cli::{ConfigInfo, ConfigSubcommand};
+use crate::shell_runner::ShellResult;
+use crate::workspace::{get_config_problems, get_config_schema, load_config_layers, Session};
+use anyhow::{bail, Result};
+use colored::Colorize;
+use std::path::Path;
+
+pub fn do_config(
+    workspace_dir_opt: Option<&Path>,
+    config_path_opt: Option<&Path>,
+    strict: bool,
+    config_info: &ConfigInfo,
+) -> Result<ShellResult> {
+    match config_info.config_subcommand {
+        ConfigSubcommand::Validate => do_validate(workspace_dir_opt, config_path_opt, strict),
+        ConfigSubcommand::Schema => {
+            println!("{}", get_config_schema()?);
+            Ok(ShellResult::Success)
+        }
+    }
+}
+
+fn do_validate(
+    workspace_dir_opt: Option<&Path>,
+    config_path_opt: Option<&Path>,
+    strict: bool,
+) -> Result<ShellResult> {
+    let (workspace_dir, config_path_opt) = Session::locate(workspace_dir_opt, config_path_opt)?;
+    let user_config_path_opt = Session::user_config_path();
+
+    let config = match load_config_layers(
+        user_config_path_opt.as_deref(),
+        &workspace_dir,
+        config_path_opt.as_deref(),
+        strict,
+    ) {
+        Ok(None) => bail!("No configuration file found"),
+        Ok(Some(loaded_config)) => {
+            for warning in loaded_config.warnings {
+                eprintln!("{}", format!("Warning: {warning}").yellow());
+            }
+            loaded_config.config
+        }
+        Err(e) => {
+            println!("{}", format!("{e}").red());
+            return Ok(ShellResult::Failure);
+        }
+    };
+
+    let config_paths = user_config_path_opt
+        .iter()
+        .chain(config_path_opt.iter())
+        .map(|p| p.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let problems = get_config_problems(&config);
+    if problems.is_empty() {
+        println!("{}", format!("{config_paths} is valid").green());
+        return Ok(ShellResult::Success);
+    }
+
+    for problem in problems {
+        println!("{}", format!("{config_paths}: {problem}").red());
+    }
+    Ok(ShellResult::Failure)
+}
@@ -28,7 +28,7 @@ use std::process::Command;
 
 pub fn do_git(session: &Session, shell_command_info: &ShellCommandInfo) -> Result<ShellResult> {
     let git_info = GitInfo::from_environment()?;
-    ShellRunner::new(shell_command_info)
+    ShellRunner::new(session, shell_command_info)?
         .run(&Plan::new(session)?, |cmd| build_command(&git_info, cmd))
 }
 
@@ -0,0 +1,349 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2020-3 Richard Cook
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy of
+// this software and associated documentation files (the "Software"), to deal in
+// the Software without restriction, including without limitation the rights to
+// use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software is furnished to do so,
+// subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+// IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+// CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+//
+use crate::cli::{GraphFormat, GraphInfo};
+use crate::workspace::{Plan, Session};
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+const SELECTED_COLOUR: &str = "#ffcc66";
+const HIGHLIGHTED_COLOUR: &str = "#99ccff";
+
+struct Node {
+    name: String,
+    project_dir: PathBuf,
+    selected: bool,
+    /// Selected project transitively depends on this project
+    upstream: bool,
+    /// This project transitively depends on selected project
+    downstream: bool,
+}
+
+impl Node {
+    const fn is_highlighted(&self) -> bool {
+        self.upstream || self.downstream
+    }
+}
+
+struct Graph {
+    nodes: Vec<Node>,
+    edges: Vec<(usize, usize)>,
+}
+
+impl Graph {
+    fn new(plan: &Plan, highlight: Option<&Path>) -> Self {
+        let deps_closure = highlight.map_or_else(HashSet::new, |d| plan.get_deps_closure(d));
+        let dependents_closure =
+            highlight.map_or_else(HashSet::new, |d| plan.get_dependents_closure(d));
+
+        let nodes = plan
+            .project_dirs_alpha
+            .iter()
+            .map(|d| Node {
+                name: Plan::get_project_name(d),
+                project_dir: d.clone(),
+                selected: highlight == Some(d.as_path()),
+                upstream: deps_closure.contains(d),
+                downstream: dependents_closure.contains(d),
+            })
+            .collect::<Vec<_>>();
+
+        let mut edges = Vec::new();
+        for (i, d) in plan.project_dirs_alpha.iter().enumerate() {
+            let mut precs = plan.get_project_precs(d);
+            precs.sort();
+            for prec in precs {
+                if let Some(j) = plan.project_dirs_alpha.iter().position(|p| *p == prec) {
+                    edges.push((i, j));
+                }
+            }
+        }
+
+        Self { nodes, edges }
+    }
+
+    // Only edges on a dependency path through selected project
+    fn is_edge_highlighted(&self, edge: (usize, usize)) -> bool {
+        let (from, to) = (&self.nodes[edge.0], &self.nodes[edge.1]);
+        (from.upstream && to.upstream) || (from.downstream && to.downstream)
+    }
+}
+
+#[derive(Serialize)]
+struct JsonGraph<'a> {
+    #[serde(rename = "nodes")]
+    nodes: Vec<JsonNode<'a>>,
+
+    #[serde(rename = "edges")]
+    edges: Vec<JsonEdge<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonNode<'a> {
+    #[serde(rename = "name")]
+    name: &'a str,
+
+    #[serde(rename = "project_dir")]
+    project_dir: &'a Path,
+
+    #[serde(rename = "selected")]
+    selected: bool,
+
+    #[serde(rename = "highlighted")]
+    highlighted: bool,
+}
+
+#[derive(Serialize)]
+struct JsonEdge<'a> {
+    #[serde(rename = "from")]
+    from: &'a str,
+
+    #[serde(rename = "to")]
+    to: &'a str,
+
+    #[serde(rename = "highlighted")]
+    highlighted: bool,
+}
+
+pub fn do_graph(session: &Session, graph_info: &GraphInfo) -> Result<()> {
+    let plan = Plan::new(session)?;
+    let highlight = match &graph_info.highlight {
+        Some(project_name) => Some(plan.find_project_dir(project_name)?),
+        None => None,
+    };
+    let graph = Graph::new(&plan, highlight.as_deref());
+    let s = match graph_info.graph_format {
+        GraphFormat::Dot => make_dot(&graph)?,
+        GraphFormat::Mermaid => make_mermaid(&graph)?,
+        GraphFormat::Json => make_json(&graph)?,
+    };
+    print!("{s}");
+    Ok(())
+}
+
+fn make_dot(graph: &Graph) -> Result<String> {
+    let mut s = String::from("digraph workspace {\n  node [shape=box];\n");
+    for node in &graph.nodes {
+        write!(s, "  \"{}\"", escape_dot(&node.name))?;
+        if node.selected {
+            write!(
+                s,
+                " [style=\"filled,bold\", fillcolor=\"{SELECTED_COLOUR}\"]"
+            )?;
+        } else if node.is_highlighted() {
+            write!(s, " [style=filled, fillcolor=\"{HIGHLIGHTED_COLOUR}\"]")?;
+        }
+        s.push_str(";\n");
+    }
+    for edge in &graph.edges {
+        write!(
+            s,
+            "  \"{}\" -> \"{}\"",
+            escape_dot(&graph.nodes[edge.0].name),
+            escape_dot(&graph.nodes[edge.1].name)
+        )?;
+        if graph.is_edge_highlighted(*edge) {
+            write!(s, " [color=\"{HIGHLIGHTED_COLOUR}\", penwidth=2]")?;
+        }
+        s.push_str(";\n");
+    }
+    s.push_str("}\n");
+    Ok(s)
+}
+
+fn make_mermaid(graph: &Graph) -> Result<String> {
+    let mut s = String::from("graph TD\n");
+    for (i, node) in graph.nodes.iter().enumerate() {
+        writeln!(s, "  n{i}[\"{}\"]", escape_mermaid(&node.name))?;
+    }
+    for (i, edge) in graph.edges.iter().enumerate() {
+        writeln!(s, "  n{} --> n{}", edge.0, edge.1)?;
+        if graph.is_edge_highlighted(*edge) {
+            writeln!(
+                s,
+                "  linkStyle {i} stroke:{HIGHLIGHTED_COLOUR},stroke-width:2px"
+            )?;
+        }
+    }
+
+    let selected = get_node_ids(graph, |n| n.selected);
+    let highlighted = get_node_ids(graph, |n| n.is_highlighted() && !n.selected);
+    if !selected.is_empty() {
+        writeln!(
+            s,
+            "  classDef selected fill:{SELECTED_COLOUR},stroke-width:2px"
+        )?;
+        writeln!(s, "  class {selected} selected")?;
+    }
+    if !highlighted.is_empty() {
+        writeln!(s, "  classDef highlighted fill:{HIGHLIGHTED_COLOUR}")?;
+        writeln!(s, "  class {highlighted} highlighted")?;
+    }
+    Ok(s)
+}
+
+fn make_json(graph: &Graph) -> Result<String> {
+    let json_graph = JsonGraph {
+        nodes: graph
+            .nodes
+            .iter()
+            .map(|n| JsonNode {
+                name: &n.name,
+                project_dir: &n.project_dir,
+                selected: n.selected,
+                highlighted: n.is_highlighted(),
+            })
+            .collect(),
+        edges: graph
+            .edges
+            .iter()
+            .map(|e| JsonEdge {
+                from: &graph.nodes[e.0].name,
+                to: &graph.nodes[e.1].name,
+                highlighted: graph.is_edge_highlighted(*e),
+            })
+            .collect(),
+    };
+    Ok(serde_json::to_string_pretty(&json_graph)? + "\n")
+}
+
+fn get_node_ids<P>(graph: &Graph, predicate: P) -> String
+where
+    P: Fn(&Node) -> bool,
+{
+    graph
+        .nodes
+        .iter()
+        .enumerate()
+        .filter(|(_, n)| predicate(n))
+        .map(|(i, _)| format!("n{i}"))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_mermaid(s: &str) -> String {
+    s.replace('"', "#quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{make_dot, make_mermaid, Graph};
+    use crate::workspace::Plan;
+    use anyhow::Result;
+    use std::collections::HashMap;
+    use std::path::{Path, PathBuf};
+
+    fn make_graph() -> Graph {
+        let plan = Plan {
+            project_dirs_alpha: vec![
+                PathBuf::from("/ws/aaa"),
+                PathBuf::from("/ws/bbb"),
+                PathBuf::from("/ws/ccc"),
+            ],
+            project_dirs_topo: Some(vec![
+                PathBuf::from("/ws/ccc"),
+                PathBuf::from("/ws/bbb"),
+                PathBuf::from("/ws/aaa"),
+            ]),
+            precs: Some(HashMap::from([
+                (PathBuf::from("/ws/aaa"), vec![PathBuf::from("/ws/ccc")]),
+                (PathBuf::from("/ws/ccc"), vec![]),
+            ])),
+        };
+        Graph::new(&plan, Some(Path::new("/ws/ccc")))
+    }
+
+    #[test]
+    fn dot() -> Result<()> {
+        assert_eq!(
+            r##"digraph workspace {
+  node [shape=box];
+  "aaa" [style=filled, fillcolor="#99ccff"];
+  "bbb";
+  "ccc" [style="filled,bold", fillcolor="#ffcc66"];
+  "aaa" -> "ccc" [color="#99ccff", penwidth=2];
+}
+"##,
+            make_dot(&make_graph())?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn mermaid() -> Result<()> {
+        assert_eq!(
+            r#"graph TD
+  n0["aaa"]
+  n1["bbb"]
+  n2["ccc"]
+  n0 --> n2
+  linkStyle 0 stroke:#99ccff,stroke-width:2px
+  classDef selected fill:#ffcc66,stroke-width:2px
+  class n2 selected
+  classDef highlighted fill:#99ccff
+  class n0 highlighted
+"#,
+            make_mermaid(&make_graph())?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn highlight_only_paths_through_selected() -> Result<()> {
+        let plan = Plan {
+            project_dirs_alpha: vec![
+                PathBuf::from("/ws/aaa"),
+                PathBuf::from("/ws/bbb"),
+                PathBuf::from("/ws/ccc"),
+            ],
+            project_dirs_topo: None,
+            precs: Some(HashMap::from([
+                (
+                    PathBuf::from("/ws/aaa"),
+                    vec![PathBuf::from("/ws/bbb"), PathBuf::from("/ws/ccc")],
+                ),
+                (PathBuf::from("/ws/bbb"), vec![PathBuf::from("/ws/ccc")]),
+                (PathBuf::from("/ws/ccc"), vec![]),
+            ])),
+        };
+        assert_eq!(
+            r##"digraph workspace {
+  node [shape=box];
+  "aaa" [style=filled, fillcolor="#99ccff"];
+  "bbb" [style="filled,bold", fillcolor="#ffcc66"];
+  "ccc" [style=filled, fillcolor="#99ccff"];
+  "aaa" -> "bbb" [color="#99ccff", penwidth=2];
+  "aaa" -> "ccc";
+  "bbb" -> "ccc" [color="#99ccff", penwidth=2];
+}
+"##,
+            make_dot(&Graph::new(&plan, Some(Path::new("/ws/bbb"))))?
+        );
+        Ok(())
+    }
+}
@@ -20,12 +20,12 @@
 // CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 //
 use crate::git::GitInfo;
+use crate::shell_runner::ShellResult;
 use crate::workspace::{Plan, Session};
-use anyhow::Result;
 use colored::Colorize;
 use std::path::PathBuf;
 
-pub fn do_info(session: &Session, show_env: bool) -> Result<()> {
+pub fn do_info(session: &Session, show_env: bool) -> ShellResult {
     println!(
         "Current working directory: {}",
         format!("{}", session.cwd.display()).cyan()
@@ -43,13 +43,47 @@ pub fn do_info(session: &Session, show_env: bool) -> Result<()> {
         )
     );
 
-    let plan = Plan::new(session)?;
-    show_project_dirs("alpha", &plan.project_dirs_alpha);
-    match &plan.project_dirs_topo {
-        Some(ds) => show_project_dirs("topo", ds),
-        None => {}
+    println!(
+        "User configuration file: {}",
+        session.user_config_path.as_ref().map_or_else(
+            || "(none)".red().italic(),
+            |x| format!("{}", x.display()).cyan()
+        )
+    );
+
+    if !session.config_sources.is_empty() {
+        println!("Configuration value sources:");
+        for (key, paths) in &session.config_sources {
+            let paths = paths
+                .iter()
+                .map(|p| {
+                    format!(
+                        "{}",
+                        p.strip_prefix(&session.workspace_dir)
+                            .unwrap_or(p)
+                            .display()
+                    )
+                })
+                .collect::<Vec<_>>();
+            println!("  {key}: {}", paths.join(", ").cyan());
+        }
     }
 
+    let shell_result = match Plan::new(session) {
+        Ok(plan) => {
+            show_project_dirs("alpha", &plan.project_dirs_alpha);
+            match &plan.project_dirs_topo {
+                Some(ds) => show_project_dirs("topo", ds),
+                None => {}
+            }
+            ShellResult::Success
+        }
+        Err(e) => {
+            println!("Configuration problem: {}", format!("{e}").red());
+            ShellResult::Failure
+        }
+    };
+
     if show_env {
         println!();
         match GitInfo::from_environment() {
@@ -64,7 +98,7 @@ pub fn do_info(session: &Session, show_env: bool) -> Result<()> {
         }
     }
 
-    Ok(())
+    shell_result
 }
 
 fn show_project_dirs(order: &str, project_dirs: &[PathBuf]) {
@@ -19,14 +19,24 @@
 // IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
 // CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 //
+mod cache;
+mod check;
+mod config;
 mod git;
+mod graph;
 mod info;
 mod init;
 mod new;
+mod query;
 mod run;
 
+pub use self::cache::do_cache;
+pub use self::check::do_check;
+pub use self::config::do_config;
 pub use self::git::do_git;
+pub use self::graph::do_graph;
 pub use self::info::do_info;
 pub use self::init::do_init;
 pub use self::new::do_new;
+pub use self::query::{do_deps, do_rdeps, do_why};
 pub use self::run::do_run;
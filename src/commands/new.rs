@@ -41,17 +41,24 @@ pub fn do_new(session: &Session) -> Result<()> {
 
     let config = Config {
         default_language: Some(Language::Lua),
-        dependency_source: None,
+        cargo_dependencies: None,
+        dependencies: None,
+        dependencies_mode: None,
+        dependency_command: None,
+        dependency_file: None,
         excluded_projects: None,
+        include: None,
         init_command: Some(Command {
             language: None,
             use_prelude: None,
             script: String::from("print(\"init_command\")"),
         }),
+        run_defaults: None,
         lua_config: Some(LanguageConfig {
             preamble: Some(String::from("print(\"preamble\")")),
             use_prelude: Some(true),
         }),
+        strict_dependencies: None,
         variables: Some(Variables::from([
             (
                 String::from("VARIABLE0"),
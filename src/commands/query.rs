@@ -0,0 +1,104 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2020-3 Richard Cook
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy of
+// this software and associated documentation files (the "Software"), to deal in
+// the Software without restriction, including without limitation the rights to
+// use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software is furnished to do so,
+// subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+// IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+// CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+//
+use crate::cli::{ProjectQueryInfo, WhyInfo};
+use crate::shell_runner::ShellResult;
+use crate::workspace::{Plan, Session};
+use anyhow::Result;
+use colored::Colorize;
+use std::path::PathBuf;
+
+pub fn do_deps(session: &Session, project_query_info: &ProjectQueryInfo) -> Result<()> {
+    let plan = Plan::new(session)?;
+    let project_dir = plan.find_project_dir(&project_query_info.project)?;
+    show_project_names(&if project_query_info.transitive {
+        plan.get_deps_closure(&project_dir)
+            .into_iter()
+            .filter(|d| *d != project_dir)
+            .collect()
+    } else {
+        plan.get_project_precs(&project_dir)
+    });
+    Ok(())
+}
+
+pub fn do_rdeps(session: &Session, project_query_info: &ProjectQueryInfo) -> Result<()> {
+    let plan = Plan::new(session)?;
+    let project_dir = plan.find_project_dir(&project_query_info.project)?;
+    show_project_names(&if project_query_info.transitive {
+        plan.get_dependents_closure(&project_dir)
+            .into_iter()
+            .filter(|d| *d != project_dir)
+            .collect()
+    } else {
+        plan.get_project_succs(&project_dir)
+    });
+    Ok(())
+}
+
+pub fn do_why(session: &Session, why_info: &WhyInfo) -> Result<ShellResult> {
+    let plan = Plan::new(session)?;
+    let from_dir = plan.find_project_dir(&why_info.from_project)?;
+    let to_dir = plan.find_project_dir(&why_info.to_project)?;
+
+    let Some(path) = plan.get_shortest_path(&from_dir, &to_dir) else {
+        println!(
+            "{}",
+            format!(
+                "{} does not depend on {}",
+                why_info.from_project, why_info.to_project
+            )
+            .yellow()
+        );
+        return Ok(ShellResult::Failure);
+    };
+
+    if why_info.transitive {
+        let dependents_closure = plan.get_dependents_closure(&to_dir);
+        show_project_names(
+            &plan
+                .get_deps_closure(&from_dir)
+                .into_iter()
+                .filter(|d| dependents_closure.contains(d))
+                .collect::<Vec<_>>(),
+        );
+    } else {
+        println!(
+            "{}",
+            path.iter()
+                .map(|d| Plan::get_project_name(d))
+                .collect::<Vec<_>>()
+                .join(" -> ")
+        );
+    }
+    Ok(ShellResult::Success)
+}
+
+fn show_project_names(project_dirs: &[PathBuf]) {
+    let mut project_names = project_dirs
+        .iter()
+        .map(|d| Plan::get_project_name(d))
+        .collect::<Vec<_>>();
+    project_names.sort();
+    for project_name in project_names {
+        println!("{project_name}");
+    }
+}
@@ -26,7 +26,7 @@ use anyhow::Result;
 use std::process::Command;
 
 pub fn do_run(session: &Session, shell_command_info: &ShellCommandInfo) -> Result<ShellResult> {
-    ShellRunner::new(shell_command_info).run(&Plan::new(session)?, build_command)
+    ShellRunner::new(session, shell_command_info)?.run(&Plan::new(session)?, build_command)
 }
 
 fn build_command(cmd: &[String]) -> Command {
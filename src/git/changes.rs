@@ -0,0 +1,187 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2020-3 Richard Cook
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy of
+// this software and associated documentation files (the "Software"), to deal in
+// the Software without restriction, including without limitation the rights to
+// use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software is furnished to do so,
+// subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+// IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+// CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+//
+use anyhow::Result;
+use git2::{Branch, ErrorCode, Repository, Status, StatusOptions};
+use std::path::Path;
+
+pub fn has_changes(project_dir: &Path, since: Option<&str>) -> Result<bool> {
+    let repo = Repository::open(project_dir)?;
+    Ok(has_uncommitted_changes(&repo)?
+        || has_unpushed_commits(&repo)?
+        || match since {
+            Some(s) => differs_from(&repo, s)?,
+            None => false,
+        })
+}
+
+fn has_uncommitted_changes(repo: &Repository) -> Result<bool> {
+    let mut status_opts = StatusOptions::new();
+    status_opts
+        .include_untracked(true)
+        .include_ignored(false)
+        .exclude_submodules(true);
+    Ok(repo
+        .statuses(Some(&mut status_opts))?
+        .iter()
+        .any(|e| e.status() != Status::CURRENT))
+}
+
+fn has_unpushed_commits(repo: &Repository) -> Result<bool> {
+    let head = match repo.head() {
+        Ok(head) => head,
+        Err(e) if e.code() == ErrorCode::UnbornBranch => return Ok(false),
+        Err(e) => return Err(e.into()),
+    };
+
+    if !head.is_branch() {
+        return Ok(false);
+    }
+
+    // Branches without an upstream, e.g. CI checkouts, are not considered changed
+    let branch = Branch::wrap(head);
+    let Ok(upstream) = branch.upstream() else {
+        return Ok(false);
+    };
+
+    match (branch.get().target(), upstream.get().target()) {
+        (Some(local), Some(remote)) => Ok(repo.graph_ahead_behind(local, remote)?.0 > 0),
+        _ => Ok(false),
+    }
+}
+
+fn differs_from(repo: &Repository, since: &str) -> Result<bool> {
+    // Treat projects in which the ref does not exist as changed
+    let since_tree = match repo.revparse_single(since) {
+        Ok(object) => object.peel_to_tree()?,
+        Err(e) if e.code() == ErrorCode::NotFound => return Ok(true),
+        Err(e) => return Err(e.into()),
+    };
+    let head_tree = match repo.head() {
+        Ok(head) => head.peel_to_tree()?,
+        Err(e) if e.code() == ErrorCode::UnbornBranch => return Ok(true),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(since_tree.id() != head_tree.id())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{has_changes, has_unpushed_commits};
+    use anyhow::Result;
+    use git2::{BranchType, Repository, Signature};
+    use std::fs::write;
+    use std::path::Path;
+    use tempfile::tempdir;
+
+    fn commit(repo: &Repository, file_name: &str) -> Result<()> {
+        let workdir = repo.workdir().expect("repository must have working tree");
+        write(workdir.join(file_name), file_name)?;
+        let mut index = repo.index()?;
+        index.add_path(Path::new(file_name))?;
+        index.write()?;
+        let tree = repo.find_tree(index.write_tree()?)?;
+        let signature = Signature::now("rws", "rws@example.com")?;
+        let parent = match repo.head() {
+            Ok(head) => Some(head.peel_to_commit()?),
+            Err(_) => None,
+        };
+        let parents = parent.iter().collect::<Vec<_>>();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            file_name,
+            &tree,
+            &parents,
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn unborn_head() -> Result<()> {
+        let temp_dir = tempdir()?;
+        _ = Repository::init(temp_dir.path())?;
+        assert!(!has_changes(temp_dir.path(), None)?);
+        assert!(has_changes(temp_dir.path(), Some("HEAD"))?);
+        Ok(())
+    }
+
+    #[test]
+    fn clean() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let repo = Repository::init(temp_dir.path())?;
+        commit(&repo, "aaa")?;
+        assert!(!has_changes(temp_dir.path(), None)?);
+        assert!(!has_changes(temp_dir.path(), Some("HEAD"))?);
+        Ok(())
+    }
+
+    #[test]
+    fn untracked_file() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let repo = Repository::init(temp_dir.path())?;
+        commit(&repo, "aaa")?;
+        write(temp_dir.path().join("bbb"), "bbb")?;
+        assert!(has_changes(temp_dir.path(), None)?);
+        Ok(())
+    }
+
+    #[test]
+    fn changed_since() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let repo = Repository::init(temp_dir.path())?;
+        commit(&repo, "aaa")?;
+        let first = repo.head()?.peel_to_commit()?;
+        _ = repo.tag_lightweight("v1", first.as_object(), false)?;
+        assert!(!has_changes(temp_dir.path(), Some("v1"))?);
+        assert!(has_changes(temp_dir.path(), Some("missing-ref"))?);
+        commit(&repo, "bbb")?;
+        assert!(has_changes(temp_dir.path(), Some("v1"))?);
+        Ok(())
+    }
+
+    #[test]
+    fn unpushed_commits() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let repo = Repository::init(temp_dir.path())?;
+        commit(&repo, "aaa")?;
+        assert!(!has_unpushed_commits(&repo)?);
+
+        let head = repo.head()?;
+        let branch_name = head.shorthand().expect("branch must have name");
+        let target = head.target().expect("branch must have target");
+        _ = repo.remote("origin", "https://example.com/repo.git")?;
+        _ = repo.reference(
+            &format!("refs/remotes/origin/{branch_name}"),
+            target,
+            false,
+            "test",
+        )?;
+        repo.find_branch(branch_name, BranchType::Local)?
+            .set_upstream(Some(&format!("origin/{branch_name}")))?;
+        assert!(!has_unpushed_commits(&repo)?);
+
+        commit(&repo, "bbb")?;
+        assert!(has_unpushed_commits(&repo)?);
+        Ok(())
+    }
+}
@@ -19,9 +19,11 @@
 // IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
 // CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 //
+mod changes;
 mod git_info;
 mod ops;
 mod submodule;
 
+pub use self::changes::has_changes;
 pub use self::git_info::GitInfo;
 pub use self::ops::clone_recursive;
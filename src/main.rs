@@ -42,7 +42,10 @@ mod util;
 mod workspace;
 
 use crate::cli::{Args, Subcommand};
-use crate::commands::{do_git, do_info, do_init, do_new, do_run};
+use crate::commands::{
+    do_cache, do_check, do_config, do_deps, do_git, do_graph, do_info, do_init, do_new, do_rdeps,
+    do_run, do_why,
+};
 use crate::shell_runner::{ShellResult, FAILURE_EXIT_CODE};
 use crate::util::reset_terminal;
 use crate::workspace::Session;
@@ -70,33 +73,54 @@ fn main() {
     })
 }
 
-fn get_session() -> Result<(Args, Session)> {
+fn run() -> Result<ShellResult> {
     let args = Args::parse();
-    let cwd = current_dir()?;
-    let session = Session::new(
-        &cwd,
-        args.workspace_dir.as_deref(),
-        args.config_path.as_deref(),
-    )?;
-    Ok((args, session))
-}
 
-fn run() -> Result<ShellResult> {
-    let (args, session) = get_session()?;
+    // Construct session on demand since invalid configuration fails session construction
+    let session = || {
+        Session::new(
+            &current_dir()?,
+            args.workspace_dir.as_deref(),
+            args.config_path.as_deref(),
+            !args.no_cache,
+            !args.lenient_config,
+        )
+    };
     Ok(match args.subcommand {
-        Subcommand::Git(shell_command_info) => do_git(&session, &shell_command_info)?,
-        Subcommand::Info => {
-            do_info(&session, true)?;
+        Subcommand::Cache(cache_info) => {
+            do_cache(&session()?, &cache_info)?;
+            ShellResult::Success
+        }
+        Subcommand::Check => do_check(&session()?),
+        Subcommand::Config(config_info) => do_config(
+            args.workspace_dir.as_deref(),
+            args.config_path.as_deref(),
+            !args.lenient_config,
+            &config_info,
+        )?,
+        Subcommand::Deps(project_query_info) => {
+            do_deps(&session()?, &project_query_info)?;
             ShellResult::Success
         }
+        Subcommand::Git(shell_command_info) => do_git(&session()?, &shell_command_info)?,
+        Subcommand::Graph(graph_info) => {
+            do_graph(&session()?, &graph_info)?;
+            ShellResult::Success
+        }
+        Subcommand::Info => do_info(&session()?, true),
         Subcommand::Init => {
-            do_init(&session)?;
+            do_init(&session()?)?;
             ShellResult::Success
         }
         Subcommand::New => {
-            do_new(&session)?;
+            do_new(&session()?)?;
+            ShellResult::Success
+        }
+        Subcommand::Rdeps(project_query_info) => {
+            do_rdeps(&session()?, &project_query_info)?;
             ShellResult::Success
         }
-        Subcommand::Run(shell_command_info) => do_run(&session, &shell_command_info)?,
+        Subcommand::Run(shell_command_info) => do_run(&session()?, &shell_command_info)?,
+        Subcommand::Why(why_info) => do_why(&session()?, &why_info)?,
     })
 }
@@ -26,15 +26,56 @@ use anyhow::Result;
 const DEFAULT_LANGUAGE: Language = Language::Lua;
 const DEFAULT_PREAMBLE: &str = "";
 const DEFAULT_USE_PRELUDE: bool = true;
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
 
 fn default_variables() -> Variables {
     Variables::new()
 }
 
+struct ResolvedCommand {
+    language: Language,
+    preamble: String,
+    script: String,
+    use_prelude: bool,
+    variables: Variables,
+}
+
 pub fn eval_script_command<T>(session: &Session, command: &Command) -> Result<T>
 where
     T: Eval,
 {
+    let c = resolve_command(session, command);
+    match c.language {
+        Language::Lua => {
+            super::lua::eval(session, &c.preamble, &c.script, c.use_prelude, &c.variables)
+        }
+    }
+}
+
+/// Stable hash of everything other than files read that determines result of command
+pub fn get_script_command_key(session: &Session, command: &Command) -> Result<String> {
+    let c = resolve_command(session, command);
+    let mut variables = c.variables.iter().collect::<Vec<_>>();
+    variables.sort_by(|a, b| a.0.cmp(b.0));
+    let s = serde_json::to_string(&(
+        &c.language,
+        &c.preamble,
+        &c.script,
+        c.use_prelude,
+        variables,
+    ))?;
+    Ok(format!("{:016x}", fnv1a(s.as_bytes())))
+}
+
+// 64-bit FNV-1a which, unlike DefaultHasher, is the same across Rust releases
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(FNV_OFFSET_BASIS, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+fn resolve_command(session: &Session, command: &Command) -> ResolvedCommand {
     let default_language = session
         .config
         .as_ref()
@@ -71,7 +112,23 @@ where
         .and_then(|d| d.variables.clone())
         .unwrap_or_else(default_variables);
 
-    match language {
-        Language::Lua => super::lua::eval(session, &preamble, &script, use_prelude, &variables),
+    ResolvedCommand {
+        language,
+        preamble,
+        script,
+        use_prelude,
+        variables,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fnv1a;
+
+    #[test]
+    fn fnv1a_basics() {
+        assert_eq!(0xcbf2_9ce4_8422_2325, fnv1a(b""));
+        assert_eq!(0xaf63_dc4c_8601_ec8c, fnv1a(b"a"));
+        assert_eq!(0x8594_4171_f739_67e8, fnv1a(b"foobar"));
     }
 }
@@ -0,0 +1,56 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2020-3 Richard Cook
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy of
+// this software and associated documentation files (the "Software"), to deal in
+// the Software without restriction, including without limitation the rights to
+// use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software is furnished to do so,
+// subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+// IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+// CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+//
+use std::cell::RefCell;
+use std::env::current_dir;
+use std::path::{Path, PathBuf};
+
+thread_local! {
+    static TRACKED_PATHS: RefCell<Option<Vec<PathBuf>>> = const { RefCell::new(None) };
+}
+
+/// Run function recording paths of files accessed through prelude functions
+pub fn track_file_access<T, F>(f: F) -> (T, Vec<PathBuf>)
+where
+    F: FnOnce() -> T,
+{
+    TRACKED_PATHS.with(|p| *p.borrow_mut() = Some(Vec::new()));
+    let result = f();
+    let paths = TRACKED_PATHS
+        .with(|p| p.borrow_mut().take())
+        .unwrap_or_default();
+    (result, paths)
+}
+
+/// Record access to file if tracking is enabled
+pub fn record_file_access(path: &Path) {
+    TRACKED_PATHS.with(|p| {
+        if let Some(paths) = p.borrow_mut().as_mut() {
+            let path = match current_dir() {
+                Ok(dir) => dir.join(path),
+                Err(_) => path.to_path_buf(),
+            };
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    });
+}
@@ -20,9 +20,11 @@
 // CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 //
 mod eval_script_command;
+mod file_tracking;
 mod lua;
 mod prelude;
 mod traits;
 mod xml;
 
-pub use self::eval_script_command::eval_script_command;
+pub use self::eval_script_command::{eval_script_command, get_script_command_key};
+pub use self::file_tracking::track_file_access;
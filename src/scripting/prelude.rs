@@ -19,6 +19,7 @@
 // IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
 // CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 //
+use super::file_tracking::record_file_access;
 use crate::git::GitInfo;
 use crate::marshal::JsonValue;
 use anyhow::{anyhow, Result};
@@ -78,11 +79,13 @@ pub fn current_dir() -> Result<String> {
 
 #[allow(clippy::unnecessary_wraps)]
 pub fn is_file(path: &Path) -> Result<bool> {
+    record_file_access(path);
     Ok(path.is_file())
 }
 
 #[allow(clippy::unnecessary_wraps)]
 pub fn is_dir(path: &Path) -> Result<bool> {
+    record_file_access(path);
     Ok(path.is_dir())
 }
 
@@ -116,11 +119,13 @@ pub mod copy_file_if_unchanged {
 
 #[allow(clippy::needless_pass_by_value)]
 pub fn read_file(path: String) -> Result<String> {
+    record_file_access(Path::new(&path));
     Ok(read_text_file(Path::new(&path))?)
 }
 
 #[allow(clippy::needless_pass_by_value)]
 pub fn read_file_lines(path: String) -> Result<Vec<String>> {
+    record_file_access(Path::new(&path));
     let f = open_file(Path::new(&path))?;
     Ok(BufReader::new(f).lines().collect::<std::io::Result<_>>()?)
 }
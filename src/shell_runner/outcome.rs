@@ -0,0 +1,119 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2020-3 Richard Cook
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy of
+// this software and associated documentation files (the "Software"), to deal in
+// the Software without restriction, including without limitation the rights to
+// use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software is furnished to do so,
+// subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+// IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+// CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+//
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+use std::time::{Duration, SystemTime};
+
+/// Outcome of running command in a project directory
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum Outcome {
+    #[serde(rename = "succeeded")]
+    Succeeded,
+
+    #[serde(rename = "failed")]
+    Failed(i32),
+
+    #[serde(rename = "signalled")]
+    Signalled(Option<i32>),
+
+    #[serde(rename = "timed_out")]
+    TimedOut,
+
+    #[serde(rename = "spawn_failed")]
+    SpawnFailed,
+
+    #[serde(rename = "skipped")]
+    Skipped,
+
+    #[serde(rename = "dependency_failed")]
+    DependencyFailed,
+}
+
+impl Outcome {
+    pub fn from_exit_status(exit_status: ExitStatus) -> Self {
+        if exit_status.success() {
+            Self::Succeeded
+        } else {
+            match exit_status.code() {
+                Some(code) => Self::Failed(code),
+                None => Self::Signalled(get_signal(exit_status)),
+            }
+        }
+    }
+
+    pub const fn is_failure(self) -> bool {
+        matches!(
+            self,
+            Self::Failed(_) | Self::Signalled(_) | Self::TimedOut | Self::SpawnFailed
+        )
+    }
+
+    pub const fn is_skipped(self) -> bool {
+        matches!(self, Self::Skipped | Self::DependencyFailed)
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Succeeded => "succeeded",
+            Self::Failed(_) => "failed",
+            Self::Signalled(_) => "signalled",
+            Self::TimedOut => "timed_out",
+            Self::SpawnFailed => "spawn_failed",
+            Self::Skipped => "skipped",
+            Self::DependencyFailed => "dependency_failed",
+        }
+    }
+}
+
+/// Result of running command in a project directory
+#[derive(Clone, Debug)]
+pub struct ProjectResult {
+    pub project_dir: PathBuf,
+    pub outcome: Outcome,
+    pub attempts: usize,
+    pub start_time: Option<SystemTime>,
+    pub duration: Option<Duration>,
+}
+
+impl ProjectResult {
+    pub fn skipped(project_dir: &Path) -> Self {
+        Self {
+            project_dir: project_dir.to_path_buf(),
+            outcome: Outcome::Skipped,
+            attempts: 0,
+            start_time: None,
+            duration: None,
+        }
+    }
+}
+
+#[cfg(unix)]
+fn get_signal(exit_status: ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    exit_status.signal()
+}
+
+#[cfg(not(unix))]
+const fn get_signal(_exit_status: ExitStatus) -> Option<i32> {
+    None
+}
@@ -0,0 +1,124 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2020-3 Richard Cook
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy of
+// this software and associated documentation files (the "Software"), to deal in
+// the Software without restriction, including without limitation the rights to
+// use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software is furnished to do so,
+// subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+// IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+// CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+//
+use crate::cli::OutputMode;
+use colored::Colorize;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+pub enum OutputLine {
+    Stdout(String),
+    Stderr(String),
+}
+
+impl OutputLine {
+    fn as_str(&self) -> &str {
+        match self {
+            Self::Stdout(s) | Self::Stderr(s) => s,
+        }
+    }
+}
+
+/// Destination for lines of output captured from a project command
+pub struct OutputSink {
+    output_mode: OutputMode,
+    prefix: String,
+    log_file: Option<Mutex<File>>,
+    lines: Mutex<Vec<OutputLine>>,
+}
+
+impl OutputSink {
+    pub fn new(output_mode: OutputMode, prefix: String, log_file: Option<File>) -> Self {
+        Self {
+            output_mode,
+            prefix,
+            log_file: log_file.map(Mutex::new),
+            lines: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn write(&self, line: OutputLine) {
+        if let Some(log_file) = &self.log_file {
+            _ = writeln!(
+                log_file.lock().expect("lock must succeed"),
+                "{}",
+                line.as_str()
+            );
+        }
+
+        match (&self.output_mode, line) {
+            (OutputMode::Passthrough, OutputLine::Stdout(s)) => println!("{s}"),
+            (OutputMode::Passthrough, OutputLine::Stderr(s)) => eprintln!("{s}"),
+            (OutputMode::Prefix, OutputLine::Stdout(s)) => println!("{} {s}", self.prefix),
+            (OutputMode::Prefix, OutputLine::Stderr(s)) => eprintln!("{} {s}", self.prefix),
+            (OutputMode::Buffered, line) => {
+                self.lines.lock().expect("lock must succeed").push(line);
+            }
+        }
+    }
+
+    /// Write notice, e.g. about retrying the command, in sequence with its output
+    pub fn write_notice(&self, s: &str) {
+        if let Some(log_file) = &self.log_file {
+            _ = writeln!(log_file.lock().expect("lock must succeed"), "{s}");
+        }
+
+        let s = s.yellow().to_string();
+        if self.output_mode == OutputMode::Buffered {
+            self.lines
+                .lock()
+                .expect("lock must succeed")
+                .push(OutputLine::Stdout(s));
+        } else {
+            println!("{s}");
+        }
+    }
+
+    /// Take lines buffered so far
+    pub fn take_lines(&self) -> Vec<OutputLine> {
+        self.lines
+            .lock()
+            .expect("lock must succeed")
+            .drain(..)
+            .collect()
+    }
+}
+
+/// Read lines from a child process stream on a background thread
+pub fn read_lines<R, F>(reader: R, sink: &Arc<OutputSink>, f: F) -> JoinHandle<()>
+where
+    R: Read + Send + 'static,
+    F: Fn(String) -> OutputLine + Send + 'static,
+{
+    let sink = Arc::clone(sink);
+    thread::spawn(move || {
+        let mut reader = BufReader::new(reader);
+        let mut buffer = Vec::new();
+        while matches!(reader.read_until(b'\n', &mut buffer), Ok(n) if n > 0) {
+            sink.write(f(String::from_utf8_lossy(&buffer)
+                .trim_end_matches(['\r', '\n'])
+                .to_string()));
+            buffer.clear();
+        }
+    })
+}
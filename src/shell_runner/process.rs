@@ -0,0 +1,208 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2020-3 Richard Cook
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy of
+// this software and associated documentation files (the "Software"), to deal in
+// the Software without restriction, including without limitation the rights to
+// use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software is furnished to do so,
+// subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+// IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+// CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+//
+use std::io;
+use std::process::{Child, Command, Stdio};
+
+/// Run command as leader of a new process group so that its descendants
+/// can be terminated along with it
+///
+/// The group is not the terminal's foreground group: terminal signals must
+/// be forwarded via `forward_signals` and the command cannot read from the
+/// terminal, so its standard input is closed if it would otherwise be one
+#[cfg(unix)]
+pub fn set_process_group(command: &mut Command) {
+    use std::io::IsTerminal;
+    use std::os::unix::process::CommandExt;
+    _ = command.process_group(0);
+    if io::stdin().is_terminal() {
+        _ = command.stdin(Stdio::null());
+    }
+}
+
+#[cfg(not(unix))]
+pub fn set_process_group(_command: &mut Command) {}
+
+#[cfg(unix)]
+pub fn kill_process_tree(child: &mut Child) -> io::Result<()> {
+    let pid = libc::pid_t::try_from(child.id())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+    // SAFETY: Sends signal to process group led by child only
+    if unsafe { libc::kill(-pid, libc::SIGKILL) } == 0 {
+        Ok(())
+    } else {
+        child.kill()
+    }
+}
+
+#[cfg(not(unix))]
+pub fn kill_process_tree(child: &mut Child) -> io::Result<()> {
+    let status = Command::new("taskkill")
+        .args(["/T", "/F", "/PID", &child.id().to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        child.kill()
+    }
+}
+
+#[cfg(unix)]
+pub use self::unix_signals::{forward_signals, is_interrupted, is_stopped, ProcessGroup};
+
+#[cfg(not(unix))]
+pub use self::other_signals::{forward_signals, is_interrupted, is_stopped, ProcessGroup};
+
+#[cfg(unix)]
+mod unix_signals {
+    use std::io;
+    use std::process::Child;
+    use std::ptr::{addr_of, addr_of_mut, null_mut};
+    use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+    use std::sync::Once;
+
+    const MAX_PROCESS_GROUPS: usize = 1024;
+
+    #[allow(clippy::declare_interior_mutable_const)]
+    const EMPTY_SLOT: AtomicI32 = AtomicI32::new(0);
+
+    static PROCESS_GROUPS: [AtomicI32; MAX_PROCESS_GROUPS] = [EMPTY_SLOT; MAX_PROCESS_GROUPS];
+    static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+    static INSTALL: Once = Once::new();
+
+    /// Forward SIGINT, SIGTERM and SIGHUP to registered process groups which
+    /// would otherwise not receive terminal signals
+    pub fn forward_signals() {
+        INSTALL.call_once(|| {
+            for signal in [libc::SIGINT, libc::SIGTERM, libc::SIGHUP] {
+                // SAFETY: Handler only performs atomic operations and calls kill
+                // which are async-signal-safe
+                unsafe {
+                    let mut action = std::mem::zeroed::<libc::sigaction>();
+                    action.sa_sigaction = handle_signal as extern "C" fn(libc::c_int) as usize;
+                    action.sa_flags = libc::SA_RESTART;
+                    _ = libc::sigemptyset(addr_of_mut!(action.sa_mask));
+                    _ = libc::sigaction(signal, addr_of!(action), null_mut());
+                }
+            }
+        });
+    }
+
+    /// Whether a forwarded signal has been received
+    pub fn is_interrupted() -> bool {
+        INTERRUPTED.load(Ordering::SeqCst)
+    }
+
+    extern "C" fn handle_signal(signal: libc::c_int) {
+        INTERRUPTED.store(true, Ordering::SeqCst);
+        for slot in &PROCESS_GROUPS {
+            let pgid = slot.load(Ordering::SeqCst);
+            if pgid != 0 {
+                // SAFETY: Sends signal to registered process group only
+                _ = unsafe { libc::kill(-pgid, signal) };
+            }
+        }
+    }
+
+    /// Registration of child's process group for signal forwarding
+    pub struct ProcessGroup {
+        slot: &'static AtomicI32,
+    }
+
+    impl ProcessGroup {
+        pub fn register(child: &Child) -> io::Result<Self> {
+            let pgid = libc::pid_t::try_from(child.id())
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            let slot = PROCESS_GROUPS
+                .iter()
+                .find(|s| {
+                    s.compare_exchange(0, pgid, Ordering::SeqCst, Ordering::SeqCst)
+                        .is_ok()
+                })
+                .ok_or_else(|| io::Error::other("Too many process groups"))?;
+
+            // Signal may have arrived before registration
+            if is_interrupted() {
+                // SAFETY: Sends signal to process group led by child only
+                _ = unsafe { libc::kill(-pgid, libc::SIGTERM) };
+            }
+
+            Ok(Self { slot })
+        }
+    }
+
+    impl Drop for ProcessGroup {
+        fn drop(&mut self) {
+            self.slot.store(0, Ordering::SeqCst);
+        }
+    }
+
+    /// Whether child has been stopped without reaping it, e.g. by SIGTTIN when
+    /// a process in a background group reads from the terminal
+    pub fn is_stopped(child: &Child) -> io::Result<bool> {
+        let pid = libc::id_t::from(child.id());
+
+        // SAFETY: WNOWAIT leaves child waitable so that Child remains consistent
+        let mut info = unsafe { std::mem::zeroed::<libc::siginfo_t>() };
+        if unsafe {
+            libc::waitid(
+                libc::P_PID,
+                pid,
+                addr_of_mut!(info),
+                libc::WSTOPPED | libc::WNOHANG | libc::WNOWAIT,
+            )
+        } != 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(info.si_signo == libc::SIGCHLD && info.si_code == libc::CLD_STOPPED)
+    }
+}
+
+#[cfg(not(unix))]
+mod other_signals {
+    use std::io;
+    use std::process::Child;
+
+    pub const fn forward_signals() {}
+
+    pub const fn is_interrupted() -> bool {
+        false
+    }
+
+    pub struct ProcessGroup;
+
+    impl ProcessGroup {
+        #[allow(clippy::unnecessary_wraps)]
+        pub const fn register(_child: &Child) -> io::Result<Self> {
+            Ok(Self)
+        }
+    }
+
+    #[allow(clippy::unnecessary_wraps)]
+    pub const fn is_stopped(_child: &Child) -> io::Result<bool> {
+        Ok(false)
+    }
+}
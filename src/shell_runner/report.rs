@@ -0,0 +1,237 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2020-3 Richard Cook
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy of
+// this software and associated documentation files (the "Software"), to deal in
+// the Software without restriction, including without limitation the rights to
+// use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software is furnished to do so,
+// subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+// IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+// CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+//
+use super::outcome::{Outcome, ProjectResult};
+use crate::cli::ReportFormat;
+use anyhow::Result;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::fs::write;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    #[serde(rename = "command")]
+    command: &'a [String],
+
+    #[serde(rename = "projects")]
+    projects: Vec<JsonProjectResult<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonProjectResult<'a> {
+    #[serde(rename = "project_dir")]
+    project_dir: &'a Path,
+
+    #[serde(rename = "command")]
+    command: &'a [String],
+
+    #[serde(rename = "status")]
+    status: &'static str,
+
+    #[serde(rename = "exit_code")]
+    exit_code: Option<i32>,
+
+    #[serde(rename = "signal")]
+    signal: Option<i32>,
+
+    #[serde(rename = "attempts")]
+    attempts: usize,
+
+    #[serde(rename = "start_time")]
+    start_time: Option<String>,
+
+    #[serde(rename = "duration")]
+    duration: Option<f64>,
+
+    #[serde(rename = "skipped")]
+    skipped: bool,
+}
+
+pub fn write_report(
+    report_format: &ReportFormat,
+    path: &Path,
+    cmd: &[String],
+    results: &[ProjectResult],
+) -> Result<()> {
+    let s = match report_format {
+        ReportFormat::Json => make_json_report(cmd, results)?,
+        ReportFormat::JUnit => make_junit_report(cmd, results)?,
+    };
+    write(path, s)?;
+    Ok(())
+}
+
+fn make_json_report(cmd: &[String], results: &[ProjectResult]) -> Result<String> {
+    let report = JsonReport {
+        command: cmd,
+        projects: results
+            .iter()
+            .map(|r| JsonProjectResult {
+                project_dir: &r.project_dir,
+                command: cmd,
+                status: r.outcome.name(),
+                exit_code: match r.outcome {
+                    Outcome::Succeeded => Some(0),
+                    Outcome::Failed(code) => Some(code),
+                    _ => None,
+                },
+                signal: match r.outcome {
+                    Outcome::Signalled(signal) => signal,
+                    _ => None,
+                },
+                attempts: r.attempts,
+                start_time: r.start_time.map(format_timestamp),
+                duration: r.duration.map(|d| d.as_secs_f64()),
+                skipped: r.outcome.is_skipped(),
+            })
+            .collect(),
+    };
+    Ok(serde_json::to_string_pretty(&report)?)
+}
+
+fn make_junit_report(cmd: &[String], results: &[ProjectResult]) -> Result<String> {
+    let command = cmd.join(" ");
+    let failure_count = results.iter().filter(|r| r.outcome.is_failure()).count();
+    let skipped_count = results.iter().filter(|r| r.outcome.is_skipped()).count();
+    let time = results
+        .iter()
+        .filter_map(|r| r.duration)
+        .map(|d| d.as_secs_f64())
+        .sum::<f64>();
+
+    let mut s = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    writeln!(
+        s,
+        "<testsuites tests=\"{}\" failures=\"{failure_count}\" skipped=\"{skipped_count}\" time=\"{time:.3}\">",
+        results.len()
+    )?;
+    write!(
+        s,
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{failure_count}\" skipped=\"{skipped_count}\" time=\"{time:.3}\"",
+        escape_xml(&command),
+        results.len()
+    )?;
+    if let Some(start_time) = results.iter().filter_map(|r| r.start_time).min() {
+        write!(s, " timestamp=\"{}\"", format_timestamp(start_time))?;
+    }
+    s.push_str(">\n");
+
+    for result in results {
+        write!(
+            s,
+            "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+            escape_xml(&result.project_dir.display().to_string()),
+            escape_xml(&command),
+            result.duration.map_or(0f64, |d| d.as_secs_f64())
+        )?;
+        let element = match result.outcome {
+            Outcome::Succeeded => None,
+            Outcome::Failed(code) => Some(format!(
+                "<failure message=\"Command exited with status {code}\"/>"
+            )),
+            Outcome::Signalled(_) => Some(String::from(
+                "<failure message=\"Command terminated by signal\"/>",
+            )),
+            Outcome::TimedOut => Some(String::from("<failure message=\"Command timed out\"/>")),
+            Outcome::SpawnFailed => Some(String::from(
+                "<failure message=\"Command could not be started\"/>",
+            )),
+            Outcome::Skipped => Some(String::from("<skipped/>")),
+            Outcome::DependencyFailed => {
+                Some(String::from("<skipped message=\"Dependency failed\"/>"))
+            }
+        };
+        match element {
+            Some(element) => writeln!(s, ">\n      {element}\n    </testcase>")?,
+            None => s.push_str("/>\n"),
+        }
+    }
+
+    s.push_str("  </testsuite>\n</testsuites>\n");
+    Ok(s)
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Format time as RFC 3339 timestamp in UTC
+fn format_timestamp(t: SystemTime) -> String {
+    let d = t.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = d.as_secs();
+    let (year, month, day) = civil_from_days(secs / 86400);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        secs % 86400 / 3600,
+        secs % 3600 / 60,
+        secs % 60,
+        d.subsec_millis()
+    )
+}
+
+// http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+const fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape_xml, format_timestamp};
+    use rstest::rstest;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[rstest]
+    #[case("1970-01-01T00:00:00.000Z", 0)]
+    #[case("2000-02-29T12:34:56.789Z", 951_827_696_789)]
+    #[case("2023-06-01T00:00:00.000Z", 1_685_577_600_000)]
+    fn format_timestamp_basics(#[case] expected_str: &str, #[case] input_millis: u64) {
+        assert_eq!(
+            expected_str,
+            format_timestamp(UNIX_EPOCH + Duration::from_millis(input_millis))
+        );
+    }
+
+    #[test]
+    fn escape_xml_basics() {
+        assert_eq!(
+            "a &lt;b&gt; &amp; &quot;c&quot; &apos;d&apos;",
+            escape_xml("a <b> & \"c\" 'd'")
+        );
+    }
+}
@@ -0,0 +1,797 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2020-3 Richard Cook
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy of
+// this software and associated documentation files (the "Software"), to deal in
+// the Software without restriction, including without limitation the rights to
+// use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software is furnished to do so,
+// subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+// IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+// CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+//
+use super::outcome::{Outcome, ProjectResult};
+use super::output::{read_lines, OutputLine, OutputSink};
+use super::process::{
+    forward_signals, is_interrupted, is_stopped, kill_process_tree, set_process_group, ProcessGroup,
+};
+use super::report::write_report;
+use super::state::RunState;
+use crate::cli::{KeepGoing, OutputMode, ProjectOrder, ReportFormat, ShellCommandInfo};
+use crate::git::has_changes;
+use crate::util::reset_terminal;
+use crate::workspace::{Plan, ProjectFilter, Session};
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+use std::fs::{create_dir_all, write, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{channel, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+pub const SUCCESS_EXIT_CODE: i32 = 0;
+pub const FAILURE_EXIT_CODE: i32 = 1;
+
+const LOG_INDEX_FILE_NAME: &str = "index.tsv";
+const RUN_STATE_FILE_NAME: &str = "last-run.json";
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+pub enum ShellResult {
+    Success,
+    Failure,
+}
+
+impl ShellResult {
+    pub const fn exit_code(&self) -> i32 {
+        match self {
+            Self::Success => SUCCESS_EXIT_CODE,
+            Self::Failure => FAILURE_EXIT_CODE,
+        }
+    }
+}
+
+struct Completion {
+    index: usize,
+    outcome: io::Result<Outcome>,
+    attempts: usize,
+    start_time: SystemTime,
+    duration: Duration,
+    lines: Vec<OutputLine>,
+}
+
+#[allow(clippy::struct_excessive_bools)]
+pub struct ShellRunner {
+    pub cmd: Vec<String>,
+    pub fail_fast: bool,
+    pub keep_going: KeepGoing,
+    pub project_order: ProjectOrder,
+    pub project_filter: ProjectFilter,
+    pub with_deps: Vec<String>,
+    pub with_dependents: Vec<String>,
+    pub changed: bool,
+    pub changed_since: Option<String>,
+    pub changed_dependents: bool,
+    pub only_failed: bool,
+    pub from_project: Option<String>,
+    pub state_path: Option<PathBuf>,
+    pub jobs: usize,
+    pub output_mode: OutputMode,
+    pub log_dir: Option<PathBuf>,
+    pub report: Option<(ReportFormat, PathBuf)>,
+    pub timeout: Option<Duration>,
+    pub retries: usize,
+    pub retry_delay: Duration,
+}
+
+impl ShellRunner {
+    pub fn new(session: &Session, shell_command_info: &ShellCommandInfo) -> Result<Self> {
+        let mut cmd = Vec::new();
+        cmd.push(shell_command_info.command.clone());
+        for arg in &shell_command_info.args {
+            cmd.push(arg.clone());
+        }
+
+        let run_defaults = session
+            .config
+            .as_ref()
+            .and_then(|c| c.run_defaults.clone())
+            .unwrap_or_default();
+
+        Ok(Self {
+            cmd,
+            fail_fast: shell_command_info.fail_fast
+                || (shell_command_info.keep_going.is_none()
+                    && run_defaults.fail_fast.unwrap_or(false)),
+            keep_going: shell_command_info
+                .keep_going
+                .clone()
+                .unwrap_or(KeepGoing::All),
+            project_order: shell_command_info
+                .project_order
+                .clone()
+                .or(run_defaults.order)
+                .unwrap_or(ProjectOrder::Topological),
+            project_filter: ProjectFilter::new(
+                &shell_command_info.project_patterns,
+                &shell_command_info.exclude_patterns,
+            )?,
+            with_deps: shell_command_info.with_deps.clone(),
+            with_dependents: shell_command_info.with_dependents.clone(),
+            changed: shell_command_info.changed
+                || shell_command_info.changed_since.is_some()
+                || shell_command_info.changed_dependents,
+            changed_since: shell_command_info.changed_since.clone(),
+            changed_dependents: shell_command_info.changed_dependents,
+            only_failed: shell_command_info.only_failed,
+            from_project: shell_command_info.from_project.clone(),
+            state_path: session
+                .config_path
+                .as_ref()
+                .map(|_| session.state_dir().join(RUN_STATE_FILE_NAME)),
+            jobs: shell_command_info.jobs.get(),
+            output_mode: shell_command_info.output_mode.clone(),
+            log_dir: shell_command_info.log_dir.clone(),
+            report: shell_command_info
+                .report
+                .as_ref()
+                .map(|path| (shell_command_info.report_format.clone(), path.clone())),
+            timeout: shell_command_info.timeout,
+            retries: shell_command_info.retries,
+            retry_delay: shell_command_info.retry_delay,
+        })
+    }
+
+    pub fn run<F>(&self, plan: &Plan, build_command: F) -> Result<ShellResult>
+    where
+        F: Fn(&[String]) -> Command,
+    {
+        let plan = &self.select_plan(plan)?;
+        let (project_dirs, project_precs) = match (&self.project_order, &plan.project_dirs_topo) {
+            (ProjectOrder::Topological, Some(ds)) => (ds, plan.precs.as_ref()),
+            _ => (&plan.project_dirs_alpha, None),
+        };
+        let project_dirs = &self.select_project_dirs(project_dirs)?;
+
+        // Projects may only start once all their predecessors have finished
+        let precs = Self::get_prec_indices(project_dirs, project_precs);
+
+        if let Some(log_dir) = &self.log_dir {
+            create_dir_all(log_dir)?;
+        }
+
+        if self.timeout.is_some() {
+            forward_signals();
+        }
+
+        let (tx, rx) = channel::<Completion>();
+        let mut results = project_dirs
+            .iter()
+            .map(|d| ProjectResult::skipped(d))
+            .collect::<Vec<_>>();
+        let mut pending = (0..project_dirs.len()).collect::<Vec<_>>();
+        let mut finished = vec![false; project_dirs.len()];
+        let mut running_count = 0;
+        let mut failure_count = 0;
+        let mut dependency_failure_count = 0;
+        let mut stopped = false;
+        loop {
+            stopped |= is_interrupted();
+            if !stopped {
+                let mut i = 0;
+                while !stopped && running_count < self.jobs && i < pending.len() {
+                    let index = pending[i];
+                    if precs[index].iter().all(|p| finished[*p]) {
+                        _ = pending.remove(i);
+                        match self.start_project(&build_command, &project_dirs[index], index, &tx) {
+                            Ok(()) => running_count += 1,
+                            Err(e) => {
+                                // Record failure and rescan since dependents may have been skipped
+                                finished[index] = true;
+                                results[index].outcome = Outcome::SpawnFailed;
+                                self.report_spawn_error(&project_dirs[index], &e);
+                                failure_count += 1;
+                                dependency_failure_count += self.handle_failure(
+                                    plan,
+                                    project_dirs,
+                                    index,
+                                    &mut pending,
+                                    &mut results,
+                                    &mut stopped,
+                                );
+                                i = 0;
+                            }
+                        }
+                    } else {
+                        i += 1;
+                    }
+                }
+            }
+
+            if running_count == 0 {
+                break;
+            }
+
+            let Completion {
+                index,
+                outcome,
+                attempts,
+                start_time,
+                duration,
+                lines,
+            } = rx.recv()?;
+            running_count -= 1;
+            finished[index] = true;
+
+            let outcome = outcome.unwrap_or_else(|e| {
+                self.report_spawn_error(&project_dirs[index], &e.into());
+                Outcome::SpawnFailed
+            });
+            results[index].outcome = outcome;
+            results[index].attempts = attempts;
+            results[index].start_time = Some(start_time);
+            results[index].duration = Some(duration);
+            self.report_completion(&project_dirs[index], outcome, lines);
+            if outcome.is_failure() {
+                failure_count += 1;
+                dependency_failure_count += self.handle_failure(
+                    plan,
+                    project_dirs,
+                    index,
+                    &mut pending,
+                    &mut results,
+                    &mut stopped,
+                );
+            }
+        }
+
+        self.finish(&results, failure_count, dependency_failure_count)
+    }
+
+    fn finish(
+        &self,
+        results: &[ProjectResult],
+        failure_count: usize,
+        dependency_failure_count: usize,
+    ) -> Result<ShellResult> {
+        if let Some(log_dir) = &self.log_dir {
+            Self::write_log_index(log_dir, results)?;
+        }
+
+        if let Some(state_path) = &self.state_path {
+            if let Err(e) = RunState::save(state_path, &self.cmd, results) {
+                eprintln!(
+                    "{}",
+                    format!("Warning: Could not save run state: {e}").yellow()
+                );
+            }
+        }
+
+        if let Some((report_format, report_path)) = &self.report {
+            write_report(report_format, report_path, &self.cmd, results)?;
+        }
+
+        if self.retries > 0 {
+            Self::show_attempts(results);
+        }
+
+        if !self.fail_fast && failure_count > 0 {
+            println!(
+                "{}",
+                format!("Command failed in {failure_count} project directories").red()
+            );
+        }
+
+        if is_interrupted() {
+            println!("{}", "Interrupted".red());
+        }
+
+        if dependency_failure_count > 0 {
+            println!(
+                "{}",
+                format!(
+                    "Skipped {dependency_failure_count} project directories due to failed dependencies"
+                )
+                .yellow()
+            );
+        }
+
+        Ok(if failure_count > 0 || is_interrupted() {
+            ShellResult::Failure
+        } else {
+            ShellResult::Success
+        })
+    }
+
+    fn handle_failure(
+        &self,
+        plan: &Plan,
+        project_dirs: &[PathBuf],
+        index: usize,
+        pending: &mut Vec<usize>,
+        results: &mut [ProjectResult],
+        stopped: &mut bool,
+    ) -> usize {
+        if self.fail_fast {
+            *stopped = true;
+            0
+        } else if self.keep_going == KeepGoing::DependentsSkip {
+            self.skip_dependents(plan, project_dirs, index, pending, results)
+        } else {
+            0
+        }
+    }
+
+    fn skip_dependents(
+        &self,
+        plan: &Plan,
+        project_dirs: &[PathBuf],
+        index: usize,
+        pending: &mut Vec<usize>,
+        results: &mut [ProjectResult],
+    ) -> usize {
+        let dependents = plan.get_dependents_closure(&project_dirs[index]);
+        let (skipped, remaining) = pending
+            .iter()
+            .partition::<Vec<usize>, _>(|i| dependents.contains(&project_dirs[**i]));
+        *pending = remaining;
+        for i in &skipped {
+            results[*i].outcome = Outcome::DependencyFailed;
+            self.report_completion(&project_dirs[*i], Outcome::DependencyFailed, Vec::new());
+        }
+        skipped.len()
+    }
+
+    fn select_plan(&self, plan: &Plan) -> Result<Plan> {
+        let closure = if self.with_deps.is_empty() && self.with_dependents.is_empty() {
+            None
+        } else {
+            let mut closure = HashSet::new();
+            for project_name in &self.with_deps {
+                closure.extend(plan.get_deps_closure(&plan.find_project_dir(project_name)?));
+            }
+            for project_name in &self.with_dependents {
+                closure.extend(plan.get_dependents_closure(&plan.find_project_dir(project_name)?));
+            }
+            Some(closure)
+        };
+
+        let changed = if self.changed {
+            Some(self.get_changed_project_dirs(plan)?)
+        } else {
+            None
+        };
+
+        let failed = if self.only_failed {
+            Some(self.get_failed_project_dirs()?)
+        } else {
+            None
+        };
+
+        Ok(plan.filter(|d| {
+            self.project_filter.matches(d)
+                && match &closure {
+                    Some(c) => c.contains(d),
+                    None => true,
+                }
+                && match &changed {
+                    Some(c) => c.contains(d),
+                    None => true,
+                }
+                && match &failed {
+                    Some(f) => f.contains(d),
+                    None => true,
+                }
+        }))
+    }
+
+    fn get_changed_project_dirs(&self, plan: &Plan) -> Result<HashSet<PathBuf>> {
+        let mut changed = HashSet::new();
+        for project_dir in &plan.project_dirs_alpha {
+            if has_changes(project_dir, self.changed_since.as_deref()).map_err(|e| {
+                anyhow!(
+                    "Could not determine Git state of {}: {e}",
+                    project_dir.display()
+                )
+            })? {
+                if self.changed_dependents {
+                    changed.extend(plan.get_dependents_closure(project_dir));
+                } else {
+                    _ = changed.insert(project_dir.clone());
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    fn get_failed_project_dirs(&self) -> Result<HashSet<PathBuf>> {
+        let state_path = self
+            .state_path
+            .as_ref()
+            .ok_or_else(|| anyhow!("--only-failed requires a workspace configuration file"))?;
+        let state = RunState::read(state_path)?
+            .filter(|state| state.command == self.cmd)
+            .ok_or_else(|| {
+                anyhow!(
+                    "No previous run of \"{}\" found at {}",
+                    self.cmd.join(" "),
+                    state_path.display()
+                )
+            })?;
+        Ok(state.failed_project_dirs())
+    }
+
+    fn select_project_dirs(&self, project_dirs: &[PathBuf]) -> Result<Vec<PathBuf>> {
+        let mut project_dirs = project_dirs.to_vec();
+
+        if let Some(from_project) = &self.from_project {
+            let i = project_dirs
+                .iter()
+                .position(|d| Plan::get_project_name(d) == *from_project)
+                .ok_or_else(|| anyhow!("Project {from_project} not found"))?;
+            _ = project_dirs.drain(..i);
+        }
+
+        Ok(project_dirs)
+    }
+
+    fn start_project<F>(
+        &self,
+        build_command: &F,
+        project_dir: &Path,
+        index: usize,
+        tx: &Sender<Completion>,
+    ) -> Result<()>
+    where
+        F: Fn(&[String]) -> Command,
+    {
+        let project_name = Plan::get_project_name(project_dir);
+        let log_file = match &self.log_dir {
+            Some(log_dir) => Some(File::create(Self::get_log_path(log_dir, &project_name))?),
+            None => None,
+        };
+
+        let mut command = build_command(&self.cmd);
+        _ = command.current_dir(project_dir);
+        if self.output_mode == OutputMode::Passthrough {
+            println!("{}", format!("{}", project_dir.display()).cyan());
+        }
+        if self.output_mode != OutputMode::Passthrough || log_file.is_some() {
+            _ = command.stdout(Stdio::piped()).stderr(Stdio::piped());
+        }
+        if self.timeout.is_some() {
+            set_process_group(&mut command);
+        }
+
+        let sink = Arc::new(OutputSink::new(
+            self.output_mode.clone(),
+            format!("[{project_name}]").cyan().to_string(),
+            log_file,
+        ));
+        let project_dir = project_dir.to_path_buf();
+        let timeout = self.timeout;
+        let retries = self.retries;
+        let retry_delay = self.retry_delay;
+        let tx = tx.clone();
+        _ = thread::spawn(move || {
+            let start_time = SystemTime::now();
+            let start = Instant::now();
+            let mut attempts = 0;
+            let outcome = loop {
+                attempts += 1;
+                let outcome = Self::run_child(&mut command, &sink, timeout);
+                if attempts > retries
+                    || is_interrupted()
+                    || !matches!(outcome, Ok(o) if o.is_failure())
+                {
+                    break outcome;
+                }
+
+                sink.write_notice(&format!(
+                    "Command failed in {} (attempt {} of {}), retrying",
+                    project_dir.display(),
+                    attempts,
+                    retries + 1
+                ));
+                thread::sleep(retry_delay);
+            };
+            tx.send(Completion {
+                index,
+                outcome,
+                attempts,
+                start_time,
+                duration: start.elapsed(),
+                lines: sink.take_lines(),
+            })
+        });
+        Ok(())
+    }
+
+    fn run_child(
+        command: &mut Command,
+        sink: &Arc<OutputSink>,
+        timeout: Option<Duration>,
+    ) -> io::Result<Outcome> {
+        let mut child = command.spawn()?;
+        let _process_group = match timeout {
+            Some(_) => match ProcessGroup::register(&child) {
+                Ok(process_group) => Some(process_group),
+                Err(e) => {
+                    _ = kill_process_tree(&mut child);
+                    _ = child.wait();
+                    return Err(e);
+                }
+            },
+            None => None,
+        };
+        let handles = [
+            child
+                .stdout
+                .take()
+                .map(|stdout| read_lines(stdout, sink, OutputLine::Stdout)),
+            child
+                .stderr
+                .take()
+                .map(|stderr| read_lines(stderr, sink, OutputLine::Stderr)),
+        ];
+        let outcome = Self::wait_for_child(&mut child, timeout);
+        for handle in handles.into_iter().flatten() {
+            _ = handle.join();
+        }
+        outcome
+    }
+
+    fn wait_for_child(child: &mut Child, timeout: Option<Duration>) -> io::Result<Outcome> {
+        let Some(timeout) = timeout else {
+            return child.wait().map(Outcome::from_exit_status);
+        };
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(exit_status) = child.try_wait()? {
+                return Ok(Outcome::from_exit_status(exit_status));
+            }
+
+            if Instant::now() >= deadline {
+                kill_process_tree(child)?;
+                _ = child.wait()?;
+                return Ok(Outcome::TimedOut);
+            }
+
+            // Process group is not in foreground so would wait for terminal until timeout
+            if is_stopped(child)? {
+                kill_process_tree(child)?;
+                _ = child.wait()?;
+                println!(
+                    "{}",
+                    "Command stopped waiting for terminal input which is unavailable with --timeout"
+                        .red()
+                );
+                return Ok(Outcome::Signalled(None));
+            }
+
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    fn report_completion(&self, project_dir: &Path, outcome: Outcome, lines: Vec<OutputLine>) {
+        if self.output_mode == OutputMode::Buffered {
+            println!("{}", format!("{}", project_dir.display()).cyan());
+            for line in lines {
+                match line {
+                    OutputLine::Stdout(s) => println!("{s}"),
+                    OutputLine::Stderr(s) => eprintln!("{s}"),
+                }
+            }
+        }
+
+        reset_terminal();
+        match outcome {
+            Outcome::Succeeded => println!(
+                "{}",
+                format!("Command succeeded in {}", project_dir.display()).green()
+            ),
+            Outcome::Failed(code) => {
+                let m = format!(
+                    "Command exited with status {} in {}",
+                    code,
+                    project_dir.display()
+                );
+                println!("{}", if self.fail_fast { m.red() } else { m.yellow() });
+            }
+            Outcome::Signalled(_) => println!(
+                "{}",
+                format!("Command terminated by signal in {}", project_dir.display()).red()
+            ),
+            Outcome::TimedOut => {
+                let m = format!(
+                    "Command timed out after {:?} in {}",
+                    self.timeout.unwrap_or_default(),
+                    project_dir.display()
+                );
+                println!("{}", if self.fail_fast { m.red() } else { m.yellow() });
+            }
+            Outcome::SpawnFailed | Outcome::Skipped => {}
+            Outcome::DependencyFailed => println!(
+                "{}",
+                format!("Skipped {} (dependency failed)", project_dir.display()).yellow()
+            ),
+        }
+    }
+
+    fn report_spawn_error(&self, project_dir: &Path, e: &anyhow::Error) {
+        let m = format!(
+            "Command could not be started in {}: {e}",
+            project_dir.display()
+        );
+        println!("{}", if self.fail_fast { m.red() } else { m.yellow() });
+    }
+
+    fn show_attempts(results: &[ProjectResult]) {
+        println!("Attempts per project directory:");
+        for result in results.iter().filter(|r| r.attempts > 0) {
+            let m = format!(
+                "  {}: {} after {} attempt(s)",
+                result.project_dir.display(),
+                result.outcome.name(),
+                result.attempts
+            );
+            println!(
+                "{}",
+                if result.outcome.is_failure() {
+                    m.red()
+                } else {
+                    m.green()
+                }
+            );
+        }
+    }
+
+    fn get_log_path(log_dir: &Path, project_name: &str) -> PathBuf {
+        log_dir.join(format!("{project_name}.log"))
+    }
+
+    fn write_log_index(log_dir: &Path, results: &[ProjectResult]) -> Result<()> {
+        let mut index = String::from("project\tstatus\tlog\n");
+        for result in results {
+            let project_name = Plan::get_project_name(&result.project_dir);
+            let log_path = Self::get_log_path(log_dir, &project_name);
+            let (status, log) = match result.outcome {
+                Outcome::Succeeded => (String::from("0"), log_path.display().to_string()),
+                Outcome::Failed(code) => (code.to_string(), log_path.display().to_string()),
+                Outcome::Signalled(_) => (String::from("signal"), log_path.display().to_string()),
+                Outcome::TimedOut => (String::from("timeout"), log_path.display().to_string()),
+                Outcome::SpawnFailed => {
+                    (String::from("spawn-failed"), log_path.display().to_string())
+                }
+                Outcome::Skipped => (String::from("not-run"), String::from("-")),
+                Outcome::DependencyFailed => (String::from("dependency-failed"), String::from("-")),
+            };
+            writeln!(index, "{project_name}\t{status}\t{log}")?;
+        }
+        write(log_dir.join(LOG_INDEX_FILE_NAME), index)?;
+        Ok(())
+    }
+
+    fn get_prec_indices(
+        project_dirs: &[PathBuf],
+        project_precs: Option<&HashMap<PathBuf, Vec<PathBuf>>>,
+    ) -> Vec<Vec<usize>> {
+        let indices = project_dirs
+            .iter()
+            .enumerate()
+            .map(|(i, d)| (d, i))
+            .collect::<HashMap<_, _>>();
+        project_dirs
+            .iter()
+            .map(|project_dir| {
+                project_precs
+                    .and_then(|ps| ps.get(project_dir))
+                    .map(|ps| ps.iter().filter_map(|p| indices.get(p).copied()).collect())
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ShellRunner;
+    use crate::cli::{Args, Subcommand};
+    use crate::shell_runner::outcome::{Outcome, ProjectResult};
+    use crate::shell_runner::state::RunState;
+    use crate::workspace::{Plan, Session};
+    use anyhow::{bail, Result};
+    use clap::Parser;
+    use std::collections::HashMap;
+    use std::fs::{create_dir_all, write};
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    #[test]
+    fn get_prec_indices_basics() {
+        let project_dirs = vec![
+            PathBuf::from("ccc"),
+            PathBuf::from("bbb"),
+            PathBuf::from("aaa"),
+        ];
+        let precs = HashMap::from([
+            (
+                PathBuf::from("aaa"),
+                vec![PathBuf::from("bbb"), PathBuf::from("ccc")],
+            ),
+            (PathBuf::from("bbb"), vec![PathBuf::from("zzz")]),
+        ]);
+
+        assert_eq!(
+            vec![Vec::<usize>::new(), vec![], vec![1, 0]],
+            ShellRunner::get_prec_indices(&project_dirs, Some(&precs))
+        );
+        assert_eq!(
+            vec![Vec::<usize>::new(), vec![], vec![]],
+            ShellRunner::get_prec_indices(&project_dirs, None)
+        );
+    }
+
+    #[test]
+    fn select_plan_only_failed_keeps_order() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let workspace_dir = temp_dir.path();
+        for project_name in ["aaa", "bbb", "ccc"] {
+            create_dir_all(workspace_dir.join(project_name).join(".git"))?;
+        }
+        write(
+            workspace_dir.join("rws-workspace.yaml"),
+            "dependencies:\n  aaa: [bbb]\n  bbb: [ccc]\n",
+        )?;
+        let session = Session::new(workspace_dir, Some(workspace_dir), None, false, true)?;
+
+        let Subcommand::Run(shell_command_info) =
+            Args::try_parse_from(["rws", "run", "--only-failed", "make"])?.subcommand
+        else {
+            bail!("Expected run subcommand")
+        };
+        let runner = ShellRunner::new(&session, &shell_command_info)?;
+        let result = |project_name: &str, outcome: Outcome| ProjectResult {
+            project_dir: workspace_dir.join(project_name),
+            outcome,
+            attempts: 1,
+            start_time: None,
+            duration: None,
+        };
+        RunState::save(
+            runner.state_path.as_ref().expect("state path must be set"),
+            &runner.cmd,
+            &[
+                result("ccc", Outcome::Failed(1)),
+                result("bbb", Outcome::Succeeded),
+                result("aaa", Outcome::Failed(1)),
+            ],
+        )?;
+
+        let plan = runner.select_plan(&Plan::new(&session)?)?;
+        let project_dirs = plan.project_dirs_topo.expect("plan must be ordered");
+        assert_eq!(
+            vec![workspace_dir.join("ccc"), workspace_dir.join("aaa")],
+            project_dirs
+        );
+        assert_eq!(
+            vec![vec![], vec![0]],
+            ShellRunner::get_prec_indices(&project_dirs, plan.precs.as_ref())
+        );
+        Ok(())
+    }
+}
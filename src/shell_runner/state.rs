@@ -0,0 +1,211 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2020-3 Richard Cook
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy of
+// this software and associated documentation files (the "Software"), to deal in
+// the Software without restriction, including without limitation the rights to
+// use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software is furnished to do so,
+// subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+// IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+// CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+//
+use super::outcome::{Outcome, ProjectResult};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{create_dir_all, read_to_string, write};
+use std::path::{Path, PathBuf};
+
+/// Per-project outcomes of most recent run of a command
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RunState {
+    #[serde(rename = "command")]
+    pub command: Vec<String>,
+
+    #[serde(rename = "projects")]
+    pub projects: Vec<ProjectState>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ProjectState {
+    #[serde(rename = "project_dir")]
+    pub project_dir: PathBuf,
+
+    #[serde(rename = "outcome")]
+    pub outcome: Outcome,
+}
+
+impl RunState {
+    pub fn read(path: &Path) -> Result<Option<Self>> {
+        if !path.is_file() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_str(&read_to_string(path)?)?))
+    }
+
+    /// Project directories in which command failed or was skipped due to a failed dependency
+    pub fn failed_project_dirs(self) -> HashSet<PathBuf> {
+        self.projects
+            .into_iter()
+            .filter(|p| p.outcome.is_failure() || p.outcome == Outcome::DependencyFailed)
+            .map(|p| p.project_dir)
+            .collect()
+    }
+
+    /// Merge results into state from previous run of same command
+    pub fn save(path: &Path, cmd: &[String], results: &[ProjectResult]) -> Result<()> {
+        let mut projects = match Self::read(path).ok().flatten() {
+            Some(state) if state.command == cmd => state.projects,
+            _ => Vec::new(),
+        };
+
+        for result in results.iter().filter(|r| r.outcome != Outcome::Skipped) {
+            match projects
+                .iter_mut()
+                .find(|p| p.project_dir == result.project_dir)
+            {
+                Some(p) => p.outcome = result.outcome,
+                None => projects.push(ProjectState {
+                    project_dir: result.project_dir.clone(),
+                    outcome: result.outcome,
+                }),
+            }
+        }
+
+        if let Some(dir) = path.parent() {
+            create_dir_all(dir)?;
+        }
+
+        let state = Self {
+            command: cmd.to_vec(),
+            projects,
+        };
+        write(path, serde_json::to_string_pretty(&state)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RunState;
+    use crate::shell_runner::outcome::{Outcome, ProjectResult};
+    use anyhow::Result;
+    use std::collections::HashSet;
+    use std::path::{Path, PathBuf};
+    use tempfile::tempdir;
+
+    fn result(project_dir: &str, outcome: Outcome) -> ProjectResult {
+        ProjectResult {
+            project_dir: PathBuf::from(project_dir),
+            outcome,
+            attempts: 1,
+            start_time: None,
+            duration: None,
+        }
+    }
+
+    fn outcomes(state: &RunState) -> Vec<(&Path, Outcome)> {
+        state
+            .projects
+            .iter()
+            .map(|p| (p.project_dir.as_path(), p.outcome))
+            .collect()
+    }
+
+    #[test]
+    fn save_merges_same_command() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let path = temp_dir.path().join("state").join("last-run.json");
+        let cmd = vec![String::from("make"), String::from("test")];
+        assert!(RunState::read(&path)?.is_none());
+
+        RunState::save(
+            &path,
+            &cmd,
+            &[
+                result("/ws/aaa", Outcome::Failed(1)),
+                result("/ws/bbb", Outcome::Succeeded),
+            ],
+        )?;
+        RunState::save(
+            &path,
+            &cmd,
+            &[
+                result("/ws/aaa", Outcome::Succeeded),
+                ProjectResult::skipped(Path::new("/ws/bbb")),
+                result("/ws/ccc", Outcome::TimedOut),
+            ],
+        )?;
+
+        let state = RunState::read(&path)?.expect("state must exist");
+        assert_eq!(cmd, state.command);
+        assert_eq!(
+            vec![
+                (Path::new("/ws/aaa"), Outcome::Succeeded),
+                (Path::new("/ws/bbb"), Outcome::Succeeded),
+                (Path::new("/ws/ccc"), Outcome::TimedOut),
+            ],
+            outcomes(&state)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn save_resets_different_command() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let path = temp_dir.path().join("last-run.json");
+        let cmd0 = vec![String::from("make"), String::from("test")];
+        let cmd1 = vec![String::from("make"), String::from("lint")];
+
+        RunState::save(&path, &cmd0, &[result("/ws/aaa", Outcome::Failed(1))])?;
+        RunState::save(&path, &cmd1, &[result("/ws/bbb", Outcome::Failed(2))])?;
+
+        let state = RunState::read(&path)?.expect("state must exist");
+        assert_eq!(cmd1, state.command);
+        assert_eq!(
+            vec![(Path::new("/ws/bbb"), Outcome::Failed(2))],
+            outcomes(&state)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn failed_project_dirs() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let path = temp_dir.path().join("last-run.json");
+        let cmd = vec![String::from("make")];
+
+        RunState::save(
+            &path,
+            &cmd,
+            &[
+                result("/ws/aaa", Outcome::Succeeded),
+                result("/ws/bbb", Outcome::Failed(1)),
+                result("/ws/ccc", Outcome::Signalled(Some(9))),
+                result("/ws/ddd", Outcome::SpawnFailed),
+                result("/ws/eee", Outcome::DependencyFailed),
+                ProjectResult::skipped(Path::new("/ws/fff")),
+            ],
+        )?;
+
+        let state = RunState::read(&path)?.expect("state must exist");
+        assert_eq!(
+            ["/ws/bbb", "/ws/ccc", "/ws/ddd", "/ws/eee"]
+                .into_iter()
+                .map(PathBuf::from)
+                .collect::<HashSet<_>>(),
+            state.failed_project_dirs()
+        );
+        Ok(())
+    }
+}
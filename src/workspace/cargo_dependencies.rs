@@ -0,0 +1,214 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2020-3 Richard Cook
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy of
+// this software and associated documentation files (the "Software"), to deal in
+// the Software without restriction, including without limitation the rights to
+// use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software is furnished to do so,
+// subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+// IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+// CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+//
+use super::config::CargoConfig;
+use anyhow::{anyhow, Result};
+use git2::Repository;
+use path_absolutize::Absolutize;
+use std::collections::BTreeSet;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+use toml::value::Table;
+use toml::Value;
+
+const CARGO_MANIFEST_FILE_NAME: &str = "Cargo.toml";
+const DEPENDENCY_TABLE_NAMES: [&str; 2] = ["dependencies", "build-dependencies"];
+const DEV_DEPENDENCY_TABLE_NAME: &str = "dev-dependencies";
+
+/// Infers dependencies between projects from path and Git dependencies
+/// in their Cargo manifests
+pub struct CargoDependencies {
+    include_dev_dependencies: bool,
+    project_dirs: Vec<PathBuf>,
+    remote_urls: Vec<(String, PathBuf)>,
+}
+
+impl CargoDependencies {
+    /// Constructor
+    pub fn new(cargo_config: &CargoConfig, project_dirs: &[PathBuf]) -> Self {
+        let remote_urls = project_dirs
+            .iter()
+            .flat_map(|d| {
+                get_remote_urls(d)
+                    .into_iter()
+                    .map(|url| (normalize_git_url(&url), d.clone()))
+            })
+            .collect();
+        Self {
+            include_dev_dependencies: cargo_config.dev_dependencies.unwrap_or(true),
+            project_dirs: project_dirs.to_vec(),
+            remote_urls,
+        }
+    }
+
+    /// Projects that project directory depends on
+    pub fn get_precs(&self, project_dir: &Path) -> Result<Vec<PathBuf>> {
+        let mut precs = BTreeSet::new();
+        for manifest_path in get_manifest_paths(project_dir)? {
+            let manifest = read_manifest(&manifest_path)?;
+            let manifest_dir = manifest_path.parent().unwrap_or(project_dir);
+            for table in self.get_dependency_tables(&manifest) {
+                for dependency in table.values() {
+                    if let Some(prec) = self.resolve(manifest_dir, dependency)? {
+                        if prec != project_dir {
+                            _ = precs.insert(prec);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(precs.into_iter().collect())
+    }
+
+    fn get_dependency_tables<'a>(&self, manifest: &'a Table) -> Vec<&'a Table> {
+        let mut table_names = DEPENDENCY_TABLE_NAMES.to_vec();
+        if self.include_dev_dependencies {
+            table_names.push(DEV_DEPENDENCY_TABLE_NAME);
+        }
+
+        let get_tables = |t: &'a Table| {
+            table_names
+                .iter()
+                .filter_map(|n| t.get(*n).and_then(Value::as_table))
+                .collect::<Vec<_>>()
+        };
+
+        let mut tables = get_tables(manifest);
+        if let Some(targets) = manifest.get("target").and_then(Value::as_table) {
+            for target in targets.values().filter_map(Value::as_table) {
+                tables.extend(get_tables(target));
+            }
+        }
+        if let Some(workspace) = manifest.get("workspace").and_then(Value::as_table) {
+            tables.extend(workspace.get("dependencies").and_then(Value::as_table));
+        }
+        tables
+    }
+
+    fn resolve(&self, manifest_dir: &Path, dependency: &Value) -> Result<Option<PathBuf>> {
+        if let Some(path) = dependency.get("path").and_then(Value::as_str) {
+            let path = manifest_dir.join(path);
+            let path = path.absolutize()?;
+            return Ok(self
+                .project_dirs
+                .iter()
+                .find(|d| path.starts_with(d))
+                .cloned());
+        }
+
+        if let Some(url) = dependency.get("git").and_then(Value::as_str) {
+            let url = normalize_git_url(url);
+            return Ok(self
+                .remote_urls
+                .iter()
+                .find(|(u, _)| *u == url)
+                .map(|(_, d)| d.clone()));
+        }
+
+        Ok(None)
+    }
+}
+
+fn read_manifest(manifest_path: &Path) -> Result<Table> {
+    toml::from_str(&read_to_string(manifest_path)?)
+        .map_err(|e| anyhow!("Could not parse {}: {e}", manifest_path.display()))
+}
+
+// Root manifest followed by manifests of any workspace members
+fn get_manifest_paths(project_dir: &Path) -> Result<Vec<PathBuf>> {
+    let manifest_path = project_dir.join(CARGO_MANIFEST_FILE_NAME);
+    if !manifest_path.is_file() {
+        return Ok(Vec::new());
+    }
+
+    let members = read_manifest(&manifest_path)?
+        .get("workspace")
+        .and_then(|w| w.get("members"))
+        .and_then(Value::as_array)
+        .map(|ms| {
+            ms.iter()
+                .filter_map(Value::as_str)
+                .map(String::from)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    let mut manifest_paths = vec![manifest_path];
+    for member in members {
+        let pattern = project_dir.join(member);
+        for member_dir in glob::glob(&pattern.to_string_lossy())?.filter_map(Result::ok) {
+            let member_manifest_path = member_dir.join(CARGO_MANIFEST_FILE_NAME);
+            if member_manifest_path.is_file() && !manifest_paths.contains(&member_manifest_path) {
+                manifest_paths.push(member_manifest_path);
+            }
+        }
+    }
+    Ok(manifest_paths)
+}
+
+fn get_remote_urls(project_dir: &Path) -> Vec<String> {
+    let Ok(repo) = Repository::open(project_dir) else {
+        return Vec::new();
+    };
+    let Ok(remote_names) = repo.remotes() else {
+        return Vec::new();
+    };
+    remote_names
+        .iter()
+        .flatten()
+        .filter_map(|name| repo.find_remote(name).ok())
+        .filter_map(|remote| remote.url().map(String::from))
+        .collect()
+}
+
+// Reduces SSH, SCP-style and HTTPS Git URLs to "host/path" for comparison
+fn normalize_git_url(url: &str) -> String {
+    let s = url.trim().trim_end_matches('/');
+    let s = s.strip_suffix(".git").unwrap_or(s);
+    let s = match s.split_once("://") {
+        Some((_, rest)) => rest.to_string(),
+        None => s.replacen(':', "/", 1),
+    };
+    let s = match s.split_once('@') {
+        Some((user, rest)) if !user.contains('/') => rest,
+        _ => &s,
+    };
+    let (host, path) = s.split_once('/').unwrap_or((s, ""));
+    let host = host.split_once(':').map_or(host, |(h, _)| h);
+    format!("{}/{}", host.to_lowercase(), path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize_git_url;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("github.com/rcook/rws", "https://github.com/rcook/rws.git")]
+    #[case("github.com/rcook/rws", "https://github.com/rcook/rws/")]
+    #[case("github.com/rcook/rws", "git@github.com:rcook/rws.git")]
+    #[case("github.com/rcook/rws", "ssh://git@github.com/rcook/rws")]
+    #[case("github.com/rcook/rws", "ssh://git@GitHub.com:22/rcook/rws.git")]
+    #[case("gitlab.com/group/sub/proj", "https://user@gitlab.com/group/sub/proj")]
+    fn normalize_git_url_basics(#[case] expected: &str, #[case] input: &str) {
+        assert_eq!(expected, normalize_git_url(input));
+    }
+}
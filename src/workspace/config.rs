@@ -19,13 +19,20 @@
 // IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
 // CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 //
+use crate::cli::ProjectOrder;
 use crate::marshal::YamlValue;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Default, Deserialize, JsonSchema, Serialize)]
 pub struct Config {
+    #[serde(rename = "include", skip_serializing_if = "Option::is_none")]
+    pub include: Option<Vec<String>>,
+
     #[serde(rename = "variables", skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<HashMap<String, serde_json::Value>>")]
     pub variables: Option<Variables>,
 
     #[serde(rename = "lua_config", skip_serializing_if = "Option::is_none")]
@@ -37,16 +44,188 @@ pub struct Config {
     #[serde(rename = "excluded_projects", skip_serializing_if = "Option::is_none")]
     pub excluded_projects: Option<Vec<String>>,
 
+    #[serde(
+        rename = "strict_dependencies",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub strict_dependencies: Option<bool>,
+
     #[serde(rename = "init_command", skip_serializing_if = "Option::is_none")]
     pub init_command: Option<Command>,
 
-    #[serde(flatten)]
-    pub dependency_source: Option<DependencySource>,
+    #[serde(rename = "dependencies", skip_serializing_if = "Option::is_none")]
+    pub dependencies: Option<StaticDependencies>,
+
+    #[serde(rename = "dependencies_mode", skip_serializing_if = "Option::is_none")]
+    pub dependencies_mode: Option<DependenciesMode>,
+
+    #[serde(rename = "dependency_command", skip_serializing_if = "Option::is_none")]
+    pub dependency_command: Option<Command>,
+
+    #[serde(rename = "dependency_file", skip_serializing_if = "Option::is_none")]
+    pub dependency_file: Option<String>,
+
+    #[serde(rename = "cargo_dependencies", skip_serializing_if = "Option::is_none")]
+    pub cargo_dependencies: Option<CargoConfig>,
+
+    #[serde(rename = "run_defaults", skip_serializing_if = "Option::is_none")]
+    pub run_defaults: Option<RunDefaults>,
+}
+
+impl Config {
+    /// Configured dependency sources with static dependencies last
+    pub fn dependency_sources(&self) -> Vec<DependencySource> {
+        let mut dependency_sources = Vec::new();
+        if let Some(command) = &self.dependency_command {
+            dependency_sources.push(DependencySource::Command(command.clone()));
+        }
+        if let Some(file_name) = &self.dependency_file {
+            dependency_sources.push(DependencySource::File(file_name.clone()));
+        }
+        if let Some(cargo_config) = &self.cargo_dependencies {
+            dependency_sources.push(DependencySource::Cargo(cargo_config.clone()));
+        }
+        if let Some(static_dependencies) = &self.dependencies {
+            dependency_sources.push(DependencySource::Static(static_dependencies.clone()));
+        }
+        dependency_sources
+    }
+
+    /// Merge other configuration over this one: maps merge, lists concatenate
+    /// and scalars override
+    pub fn merge(&mut self, other: Self, source: &Path, sources: &mut ConfigSources) {
+        let mut recorder = SourceRecorder { source, sources };
+
+        if let Some(variables) = other.variables {
+            let target = self.variables.get_or_insert_with(HashMap::new);
+            for (name, value) in variables {
+                recorder.set(&format!("variables.{name}"));
+                target.insert(name, value);
+            }
+        }
+
+        if let Some(lua_config) = other.lua_config {
+            let target = self.lua_config.get_or_insert_with(LanguageConfig::default);
+            recorder.merge(
+                "lua_config.preamble",
+                &mut target.preamble,
+                lua_config.preamble,
+            );
+            recorder.merge(
+                "lua_config.use_prelude",
+                &mut target.use_prelude,
+                lua_config.use_prelude,
+            );
+        }
+
+        recorder.merge(
+            "default_language",
+            &mut self.default_language,
+            other.default_language,
+        );
+
+        if let Some(excluded_projects) = other.excluded_projects {
+            recorder.add("excluded_projects");
+            let target = self.excluded_projects.get_or_insert_with(Vec::new);
+            for project_name in excluded_projects {
+                if !target.contains(&project_name) {
+                    target.push(project_name);
+                }
+            }
+        }
+
+        recorder.merge(
+            "strict_dependencies",
+            &mut self.strict_dependencies,
+            other.strict_dependencies,
+        );
+        recorder.merge("init_command", &mut self.init_command, other.init_command);
+
+        if let Some(dependencies) = other.dependencies {
+            let target = self.dependencies.get_or_insert_with(HashMap::new);
+            for (project_name, precs) in dependencies {
+                recorder.add(&format!("dependencies.{project_name}"));
+                let target_precs = target.entry(project_name).or_default();
+                for prec in precs {
+                    if !target_precs.contains(&prec) {
+                        target_precs.push(prec);
+                    }
+                }
+            }
+        }
+
+        recorder.merge(
+            "dependencies_mode",
+            &mut self.dependencies_mode,
+            other.dependencies_mode,
+        );
+        recorder.merge(
+            "dependency_command",
+            &mut self.dependency_command,
+            other.dependency_command,
+        );
+        recorder.merge(
+            "dependency_file",
+            &mut self.dependency_file,
+            other.dependency_file,
+        );
+
+        if let Some(cargo_config) = other.cargo_dependencies {
+            recorder.add("cargo_dependencies");
+            let target = self
+                .cargo_dependencies
+                .get_or_insert_with(CargoConfig::default);
+            recorder.merge(
+                "cargo_dependencies.dev_dependencies",
+                &mut target.dev_dependencies,
+                cargo_config.dev_dependencies,
+            );
+        }
+
+        if let Some(run_defaults) = other.run_defaults {
+            let target = self.run_defaults.get_or_insert_with(RunDefaults::default);
+            recorder.merge(
+                "run_defaults.fail_fast",
+                &mut target.fail_fast,
+                run_defaults.fail_fast,
+            );
+            recorder.merge("run_defaults.order", &mut target.order, run_defaults.order);
+        }
+    }
+}
+
+/// Files that contributed each configuration value keyed by dotted path
+pub type ConfigSources = BTreeMap<String, Vec<PathBuf>>;
+
+struct SourceRecorder<'a> {
+    source: &'a Path,
+    sources: &'a mut ConfigSources,
+}
+
+impl SourceRecorder<'_> {
+    fn set(&mut self, key: &str) {
+        self.sources
+            .insert(String::from(key), vec![self.source.to_path_buf()]);
+    }
+
+    fn add(&mut self, key: &str) {
+        let paths = self.sources.entry(String::from(key)).or_default();
+        if !paths.iter().any(|p| p == self.source) {
+            paths.push(self.source.to_path_buf());
+        }
+    }
+
+    fn merge<T>(&mut self, key: &str, target: &mut Option<T>, value: Option<T>) {
+        if let Some(value) = value {
+            self.set(key);
+            *target = Some(value);
+        }
+    }
 }
 
 pub type Variables = HashMap<String, YamlValue>;
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, Serialize)]
 pub struct LanguageConfig {
     #[serde(rename = "preamble", skip_serializing_if = "Option::is_none")]
     pub preamble: Option<String>,
@@ -55,13 +234,13 @@ pub struct LanguageConfig {
     pub use_prelude: Option<bool>,
 }
 
-#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Serialize)]
 pub enum Language {
     #[serde(rename = "lua")]
     Lua,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Serialize)]
 pub struct Command {
     #[serde(rename = "language", skip_serializing_if = "Option::is_none")]
     pub language: Option<Language>,
@@ -73,20 +252,46 @@ pub struct Command {
     pub script: String,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum DependencySource {
-    #[serde(rename = "dependencies")]
     Static(StaticDependencies),
-
-    #[serde(rename = "dependency_command")]
     Command(Command),
+    File(String),
+    Cargo(CargoConfig),
+}
+
+/// How static dependencies combine with computed dependencies of the same project
+#[derive(Clone, Copy, Debug, Default, Deserialize, JsonSchema, PartialEq, Serialize)]
+pub enum DependenciesMode {
+    #[default]
+    #[serde(rename = "merge")]
+    Merge,
+
+    #[serde(rename = "replace")]
+    Replace,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, PartialEq, Serialize)]
+pub struct CargoConfig {
+    #[serde(rename = "dev_dependencies", skip_serializing_if = "Option::is_none")]
+    pub dev_dependencies: Option<bool>,
+}
+
+/// Defaults for options of commands run in project directories
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, PartialEq, Serialize)]
+pub struct RunDefaults {
+    #[serde(rename = "fail_fast", skip_serializing_if = "Option::is_none")]
+    pub fail_fast: Option<bool>,
+
+    #[serde(rename = "order", skip_serializing_if = "Option::is_none")]
+    pub order: Option<ProjectOrder>,
 }
 
 pub type StaticDependencies = HashMap<String, Vec<String>>;
 
 #[cfg(test)]
 mod tests {
-    use super::{Command, Config, DependencySource, Language};
+    use super::{CargoConfig, Command, Config, DependencySource, Language};
     use crate::marshal::YamlValue;
     use anyhow::Result;
     use rstest::rstest;
@@ -162,9 +367,7 @@ print("Hello from init_command")
             init_command.script,
         );
 
-        let Some(DependencySource::Command(dependency_command)) = config.dependency_source else {
-            panic!("Expected dependency_command")
-        };
+        let dependency_command = config.dependency_command.expect("must be present");
         assert!(dependency_command.language.is_none());
         assert!(dependency_command.use_prelude.is_none());
         assert_eq!(
@@ -186,10 +389,10 @@ end
 
     #[rstest]
     #[case(
-        Some(DependencySource::Static(HashMap::from([
+        vec![DependencySource::Static(HashMap::from([
             (String::from("aaa"), vec![String::from("bbb"), String::from("ccc")]),
             (String::from("ddd"), vec![String::from("eee"), String::from("fff")])
-        ]))),
+        ]))],
         r#"dependencies:
   aaa:
     - bbb
@@ -199,17 +402,43 @@ end
     - fff"#
     )]
     #[case(
-        Some(DependencySource::Command(Command{language:None, use_prelude:None, script: String::from("SCRIPT")})),
+        vec![DependencySource::Command(Command{language:None, use_prelude:None, script: String::from("SCRIPT")})],
         r#"dependency_command:
   script: SCRIPT"#
     )]
-    #[case(None, "")]
-    fn dependency_source(
-        #[case] expected_dependency_source: Option<DependencySource>,
+    #[case(
+        vec![DependencySource::File(String::from("_DEPS"))],
+        "dependency_file: _DEPS"
+    )]
+    #[case(
+        vec![DependencySource::Cargo(CargoConfig::default())],
+        "cargo_dependencies: {}"
+    )]
+    #[case(
+        vec![DependencySource::Cargo(CargoConfig{dev_dependencies: Some(false)})],
+        r"cargo_dependencies:
+  dev_dependencies: false"
+    )]
+    #[case(
+        vec![
+            DependencySource::Command(Command{language:None, use_prelude:None, script: String::from("SCRIPT")}),
+            DependencySource::Static(HashMap::from([
+                (String::from("aaa"), vec![String::from("bbb")])
+            ]))
+        ],
+        r"dependencies:
+  aaa:
+    - bbb
+dependency_command:
+  script: SCRIPT"
+    )]
+    #[case(vec![], "")]
+    fn dependency_sources(
+        #[case] expected_dependency_sources: Vec<DependencySource>,
         #[case] input: &str,
     ) -> Result<()> {
         let config = from_str::<Config>(input)?;
-        assert_eq!(expected_dependency_source, config.dependency_source);
+        assert_eq!(expected_dependency_sources, config.dependency_sources());
         Ok(())
     }
 }
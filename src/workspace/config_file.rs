@@ -0,0 +1,788 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2020-3 Richard Cook
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy of
+// this software and associated documentation files (the "Software"), to deal in
+// the Software without restriction, including without limitation the rights to
+// use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software is furnished to do so,
+// subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+// IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+// CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+//
+use super::config::{Config, ConfigSources};
+use super::interpolation::interpolate;
+use crate::marshal::YamlValue;
+use anyhow::{anyhow, bail, Result};
+use schemars::schema::{RootSchema, Schema, SchemaObject, SingleOrVec};
+use schemars::schema_for;
+use serde_ignored::Path as IgnoredPath;
+use std::collections::HashMap;
+use std::env::var;
+use std::ffi::OsStr;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+use strsim::jaro_winkler;
+
+const SUGGESTION_THRESHOLD: f64 = 0.8;
+
+/// Configuration file formats
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ConfigFormat {
+    Yaml,
+    Toml,
+    Json,
+}
+
+impl ConfigFormat {
+    /// Format implied by file extension defaulting to YAML
+    fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(OsStr::to_str) {
+            Some("toml") => Self::Toml,
+            Some("json") => Self::Json,
+            _ => Self::Yaml,
+        }
+    }
+}
+
+/// Effective configuration merged from configuration file and its includes
+#[derive(Debug, Default)]
+pub struct LoadedConfig {
+    pub config: Config,
+    pub sources: ConfigSources,
+    pub warnings: Vec<String>,
+}
+
+/// Read user configuration file, if any, with workspace configuration file
+/// merged over it and files they include relative to their directories
+pub fn load_config_layers(
+    user_config_path_opt: Option<&Path>,
+    workspace_dir: &Path,
+    config_path_opt: Option<&Path>,
+    strict: bool,
+) -> Result<Option<LoadedConfig>> {
+    if user_config_path_opt.is_none() && config_path_opt.is_none() {
+        return Ok(None);
+    }
+
+    let mut loaded_config = LoadedConfig::default();
+    if let Some(user_config_path) = user_config_path_opt {
+        let user_config_dir = user_config_path
+            .parent()
+            .ok_or_else(|| anyhow!("Invalid user config path"))?;
+        load_config_layer(
+            user_config_dir,
+            user_config_path,
+            strict,
+            &mut Vec::new(),
+            &mut loaded_config,
+        )?;
+    }
+    if let Some(config_path) = config_path_opt {
+        load_config_layer(
+            workspace_dir,
+            config_path,
+            strict,
+            &mut Vec::new(),
+            &mut loaded_config,
+        )?;
+    }
+    Ok(Some(loaded_config))
+}
+
+fn load_config_layer(
+    workspace_dir: &Path,
+    path: &Path,
+    strict: bool,
+    stack: &mut Vec<PathBuf>,
+    loaded_config: &mut LoadedConfig,
+) -> Result<()> {
+    if stack.iter().any(|p| p == path) {
+        let mut cycle = stack
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect::<Vec<_>>();
+        cycle.push(path.display().to_string());
+        bail!(
+            "Configuration include cycle detected: {}",
+            cycle.join(" -> ")
+        )
+    }
+
+    let (mut config, warnings) = read_config_file(path, strict)?;
+    loaded_config.warnings.extend(warnings);
+
+    stack.push(path.to_path_buf());
+    for include in config.include.take().unwrap_or_default() {
+        load_config_layer(
+            workspace_dir,
+            &workspace_dir.join(include),
+            strict,
+            stack,
+            loaded_config,
+        )?;
+    }
+    stack.pop();
+
+    loaded_config
+        .config
+        .merge(config, path, &mut loaded_config.sources);
+    Ok(())
+}
+
+/// Read configuration file reporting parse errors with file, line and column
+///
+/// Unknown keys are errors unless `strict` is false in which case they are
+/// returned as warnings
+pub fn read_config_file(path: &Path, strict: bool) -> Result<(Config, Vec<String>)> {
+    let s = read_to_string(path).map_err(|e| anyhow!("{}: {e}", path.display()))?;
+    parse_config(path, &s, strict)
+}
+
+fn parse_config(path: &Path, s: &str, strict: bool) -> Result<(Config, Vec<String>)> {
+    let mut unknown_keys = Vec::new();
+    let result = match ConfigFormat::from_path(path) {
+        ConfigFormat::Yaml => {
+            deserialize_config(serde_yaml::Deserializer::from_str(s), &mut unknown_keys)
+                .map_err(|e| get_yaml_error_message(path, &e))
+        }
+        ConfigFormat::Toml => deserialize_config(toml::Deserializer::new(s), &mut unknown_keys)
+            .map_err(|e| get_toml_error_message(path, s, &e)),
+        ConfigFormat::Json => {
+            let mut deserializer = serde_json::Deserializer::from_str(s);
+            deserialize_config(&mut deserializer, &mut unknown_keys)
+                .and_then(|config| deserializer.end().map(|()| config))
+                .map_err(|e| get_json_error_message(path, &e))
+        }
+    };
+
+    let schema = schema_for!(Config);
+    let unknown_key_messages = unknown_keys
+        .iter()
+        .map(|k| {
+            format!(
+                "{}: {}",
+                path.display(),
+                get_unknown_key_message(&schema, k)
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut config = match result {
+        Ok(config) => config,
+        Err(e) => {
+            // Unknown keys often explain errors such as missing fields
+            let mut lines = unknown_key_messages;
+            lines.push(e);
+            bail!(lines.join("\n"))
+        }
+    };
+
+    if strict && !unknown_key_messages.is_empty() {
+        bail!(unknown_key_messages.join("\n"))
+    }
+
+    interpolate_config(&mut config, |name| var(name).ok())
+        .map_err(|e| anyhow!("{}: {e}", path.display()))?;
+
+    Ok((config, unknown_key_messages))
+}
+
+/// Expand environment variable references in data values but not in scripts
+fn interpolate_config<F>(config: &mut Config, lookup: F) -> Result<()>
+where
+    F: Fn(&str) -> Option<String> + Copy,
+{
+    let expand = |key: &str, s: &str| interpolate(s, lookup).map_err(|e| anyhow!("{key}: {e}"));
+
+    for (key, values) in [
+        ("include", &mut config.include),
+        ("excluded_projects", &mut config.excluded_projects),
+    ] {
+        for value in values.iter_mut().flatten() {
+            *value = expand(key, value)?;
+        }
+    }
+
+    if let Some(file_name) = &mut config.dependency_file {
+        *file_name = expand("dependency_file", file_name)?;
+    }
+
+    if let Some(dependencies) = config.dependencies.take() {
+        let mut result = HashMap::new();
+        for (project_name, precs) in dependencies {
+            let key = format!("dependencies.{project_name}");
+            let precs = precs
+                .iter()
+                .map(|p| expand(&key, p))
+                .collect::<Result<Vec<_>>>()?;
+            result.insert(expand("dependencies", &project_name)?, precs);
+        }
+        config.dependencies = Some(result);
+    }
+
+    if let Some(variables) = &mut config.variables {
+        for (name, value) in variables.iter_mut() {
+            interpolate_yaml_value(value, lookup).map_err(|e| anyhow!("variables.{name}: {e}"))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn interpolate_yaml_value<F>(value: &mut YamlValue, lookup: F) -> Result<()>
+where
+    F: Fn(&str) -> Option<String> + Copy,
+{
+    match value {
+        YamlValue::String(s) => *s = interpolate(s, lookup)?,
+        YamlValue::Sequence(items) => {
+            for item in items {
+                interpolate_yaml_value(item, lookup)?;
+            }
+        }
+        YamlValue::Mapping(mapping) => {
+            for (_, item) in mapping.iter_mut() {
+                interpolate_yaml_value(item, lookup)?;
+            }
+        }
+        YamlValue::Tagged(tagged) => interpolate_yaml_value(&mut tagged.value, lookup)?,
+        _ => {}
+    }
+    Ok(())
+}
+
+fn deserialize_config<'de, D>(
+    deserializer: D,
+    unknown_keys: &mut Vec<Vec<String>>,
+) -> std::result::Result<Config, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    serde_ignored::deserialize(deserializer, |p| unknown_keys.push(get_key_path(&p)))
+}
+
+fn get_yaml_error_message(path: &Path, e: &serde_yaml::Error) -> String {
+    let message = e.to_string();
+    match e.location() {
+        Some(location) => {
+            let suffix = format!(" at line {} column {}", location.line(), location.column());
+            get_location_error_message(
+                path,
+                location.line(),
+                location.column(),
+                message.strip_suffix(&suffix).unwrap_or(&message),
+            )
+        }
+        None => format!("{}: {message}", path.display()),
+    }
+}
+
+fn get_toml_error_message(path: &Path, s: &str, e: &toml::de::Error) -> String {
+    let message = e.message().trim_end();
+    match e.span() {
+        Some(span) => {
+            let before = &s[..span.start.min(s.len())];
+            let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+            get_location_error_message(
+                path,
+                before.matches('\n').count() + 1,
+                before[line_start..].chars().count() + 1,
+                message,
+            )
+        }
+        None => format!("{}: {message}", path.display()),
+    }
+}
+
+fn get_json_error_message(path: &Path, e: &serde_json::Error) -> String {
+    let message = e.to_string();
+    if e.line() == 0 {
+        return format!("{}: {message}", path.display());
+    }
+
+    let suffix = format!(" at line {} column {}", e.line(), e.column());
+    get_location_error_message(
+        path,
+        e.line(),
+        e.column(),
+        message.strip_suffix(&suffix).unwrap_or(&message),
+    )
+}
+
+fn get_location_error_message(path: &Path, line: usize, column: usize, message: &str) -> String {
+    format!("{}:{line}:{column}: {message}", path.display())
+}
+
+fn get_key_path(path: &IgnoredPath) -> Vec<String> {
+    fn visit(path: &IgnoredPath, keys: &mut Vec<String>) {
+        match path {
+            IgnoredPath::Root => {}
+            IgnoredPath::Seq { parent, index } => {
+                visit(parent, keys);
+                keys.push(index.to_string());
+            }
+            IgnoredPath::Map { parent, key } => {
+                visit(parent, keys);
+                keys.push(key.clone());
+            }
+            IgnoredPath::Some { parent }
+            | IgnoredPath::NewtypeStruct { parent }
+            | IgnoredPath::NewtypeVariant { parent } => visit(parent, keys),
+        }
+    }
+
+    let mut keys = Vec::new();
+    visit(path, &mut keys);
+    keys
+}
+
+fn get_unknown_key_message(schema: &RootSchema, key_path: &[String]) -> String {
+    let Some((key, parent_keys)) = key_path.split_last() else {
+        return String::from("unknown key");
+    };
+
+    let suggestion = get_valid_keys(schema, parent_keys)
+        .into_iter()
+        .map(|k| (jaro_winkler(key, &k), k))
+        .filter(|(score, _)| *score >= SUGGESTION_THRESHOLD)
+        .max_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, k)| k);
+
+    match suggestion {
+        Some(s) => format!("unknown key `{}`, did you mean `{s}`?", key_path.join(".")),
+        None => format!("unknown key `{}`", key_path.join(".")),
+    }
+}
+
+fn get_valid_keys(schema: &RootSchema, parent_keys: &[String]) -> Vec<String> {
+    let mut obj = &schema.schema;
+    for key in parent_keys {
+        let next = obj
+            .object
+            .as_ref()
+            .and_then(|o| o.properties.get(key).or(o.additional_properties.as_deref()));
+        let next = next.or_else(|| match obj.array.as_ref().and_then(|a| a.items.as_ref()) {
+            Some(SingleOrVec::Single(items)) => Some(items.as_ref()),
+            _ => None,
+        });
+        let Some(next) = next.and_then(|s| resolve_schema(schema, s)) else {
+            return Vec::new();
+        };
+        obj = next;
+    }
+
+    obj.object
+        .as_ref()
+        .map(|o| o.properties.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+fn resolve_schema<'a>(root: &'a RootSchema, schema: &'a Schema) -> Option<&'a SchemaObject> {
+    let Schema::Object(obj) = schema else {
+        return None;
+    };
+
+    if let Some(reference) = &obj.reference {
+        let name = reference.strip_prefix("#/definitions/")?;
+        return resolve_schema(root, root.definitions.get(name)?);
+    }
+
+    // Optional values are represented as "anyOf" with null
+    if let Some(any_of) = obj.subschemas.as_ref().and_then(|s| s.any_of.as_ref()) {
+        return any_of
+            .iter()
+            .filter_map(|s| resolve_schema(root, s))
+            .find(|o| o.object.is_some());
+    }
+
+    Some(obj)
+}
+
+/// Semantic problems with configuration that parsing does not detect
+pub fn get_config_problems(config: &Config) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    for (name, command) in [
+        ("init_command", &config.init_command),
+        ("dependency_command", &config.dependency_command),
+    ] {
+        if command.as_ref().is_some_and(|c| c.script.trim().is_empty()) {
+            problems.push(format!("{name}: script is empty"));
+        }
+    }
+
+    if config
+        .dependency_file
+        .as_ref()
+        .is_some_and(|f| f.trim().is_empty())
+    {
+        problems.push(String::from("dependency_file: file name is empty"));
+    }
+
+    if let Some(dependencies) = &config.dependencies {
+        let mut project_names = dependencies.keys().collect::<Vec<_>>();
+        project_names.sort();
+        for project_name in project_names {
+            if dependencies[project_name].contains(project_name) {
+                problems.push(format!(
+                    "dependencies: project {project_name} depends on itself"
+                ));
+            }
+            if dependencies[project_name]
+                .iter()
+                .any(|p| p.trim().is_empty())
+            {
+                problems.push(format!(
+                    "dependencies: project {project_name} has empty dependency name"
+                ));
+            }
+        }
+    } else if config.dependencies_mode.is_some() {
+        problems.push(String::from(
+            "dependencies_mode: has no effect without dependencies",
+        ));
+    }
+
+    problems
+}
+
+/// JSON Schema describing configuration file
+pub fn get_config_schema() -> Result<String> {
+    Ok(serde_json::to_string_pretty(&schema_for!(Config))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        get_config_problems, interpolate_config, load_config_layers, parse_config, Config,
+    };
+    use crate::cli::ProjectOrder;
+    use anyhow::Result;
+    use serde_yaml::from_str;
+    use std::fs::{create_dir_all, write};
+    use std::path::Path;
+    use tempfile::tempdir;
+
+    #[test]
+    fn parse_config_error_location() {
+        let e = parse_config(
+            Path::new("rws-workspace.yaml"),
+            "excluded_projects: []\ndefault_language: python\n",
+            true,
+        )
+        .expect_err("must fail");
+        assert_eq!(
+            "rws-workspace.yaml:2:19: default_language: unknown variant `python`, expected `lua`",
+            e.to_string()
+        );
+    }
+
+    #[test]
+    fn config_problems() -> Result<()> {
+        let config = parse_config(
+            Path::new("rws-workspace.yaml"),
+            r#"dependencies_mode: replace
+dependency_command:
+  script: "  "
+dependency_file: ""
+"#,
+            true,
+        )?
+        .0;
+        assert_eq!(
+            vec![
+                "dependency_command: script is empty",
+                "dependency_file: file name is empty",
+                "dependencies_mode: has no effect without dependencies"
+            ],
+            get_config_problems(&config)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_config_unknown_keys() -> Result<()> {
+        let s = r#"exclude_projects: []
+init_command:
+  script: ""
+  langauge: lua
+cargo_dependencies:
+  frobnicate: true
+"#;
+        let e = parse_config(Path::new("rws-workspace.yaml"), s, true).expect_err("must fail");
+        assert_eq!(
+            "rws-workspace.yaml: unknown key `exclude_projects`, did you mean `excluded_projects`?\n\
+             rws-workspace.yaml: unknown key `init_command.langauge`, did you mean `language`?\n\
+             rws-workspace.yaml: unknown key `cargo_dependencies.frobnicate`",
+            e.to_string()
+        );
+
+        let (config, warnings) = parse_config(Path::new("rws-workspace.yaml"), s, false)?;
+        assert!(config.init_command.is_some());
+        assert_eq!(3, warnings.len());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_config_unknown_key_with_missing_field() {
+        let e = parse_config(
+            Path::new("rws-workspace.yaml"),
+            "init_command:\n  scirpt: \"\"\n",
+            false,
+        )
+        .expect_err("must fail");
+        assert!(e.to_string().starts_with(
+            "rws-workspace.yaml: unknown key `init_command.scirpt`, did you mean `script`?\n"
+        ));
+    }
+
+    #[test]
+    fn load_config_includes() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let workspace_dir = temp_dir.path();
+        let config_path = workspace_dir.join("rws-workspace.yaml");
+        let shared_path = workspace_dir.join("shared.yaml");
+        write(
+            &config_path,
+            r"include:
+  - shared.yaml
+variables:
+  KEY1: override
+excluded_projects: [bbb]
+dependencies:
+  aaa: [ccc]
+",
+        )?;
+        write(
+            &shared_path,
+            r#"variables:
+  KEY0: shared
+  KEY1: shared
+lua_config:
+  preamble: print("shared")
+excluded_projects: [aaa, bbb]
+dependencies:
+  aaa: [bbb]
+"#,
+        )?;
+
+        let loaded_config = load_config_layers(None, workspace_dir, Some(&config_path), true)?
+            .expect("must be present");
+        let config = loaded_config.config;
+        let variables = config.variables.expect("must be present");
+        assert_eq!(
+            "shared",
+            variables["KEY0"].as_str().expect("must be string")
+        );
+        assert_eq!(
+            "override",
+            variables["KEY1"].as_str().expect("must be string")
+        );
+        assert_eq!(
+            Some(vec![String::from("aaa"), String::from("bbb")]),
+            config.excluded_projects
+        );
+        assert_eq!(
+            vec![String::from("bbb"), String::from("ccc")],
+            config.dependencies.expect("must be present")["aaa"]
+        );
+
+        let sources = loaded_config.sources;
+        assert_eq!(vec![shared_path.clone()], sources["variables.KEY0"]);
+        assert_eq!(vec![config_path.clone()], sources["variables.KEY1"]);
+        assert_eq!(vec![shared_path.clone()], sources["lua_config.preamble"]);
+        assert_eq!(vec![shared_path, config_path], sources["excluded_projects"]);
+        Ok(())
+    }
+
+    #[test]
+    fn load_config_include_cycle() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let workspace_dir = temp_dir.path();
+        write(workspace_dir.join("a.yaml"), "include: [b.yaml]\n")?;
+        write(workspace_dir.join("b.yaml"), "include: [a.yaml]\n")?;
+
+        let path = workspace_dir.join("a.yaml");
+        let e = load_config_layers(None, workspace_dir, Some(&path), true).expect_err("must fail");
+        let paths = ["a.yaml", "b.yaml", "a.yaml"]
+            .iter()
+            .map(|p| workspace_dir.join(p).display().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            format!(
+                "Configuration include cycle detected: {}",
+                paths.join(" -> ")
+            ),
+            e.to_string()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn load_config_layers_user_config() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let user_config_dir = temp_dir.path().join("user");
+        let workspace_dir = temp_dir.path().join("workspace");
+        create_dir_all(&user_config_dir)?;
+        create_dir_all(&workspace_dir)?;
+        let user_config_path = user_config_dir.join("config.yaml");
+        let config_path = workspace_dir.join("rws-workspace.yaml");
+        write(
+            user_config_dir.join("helpers.yaml"),
+            "lua_config:\n  use_prelude: true\n",
+        )?;
+        write(
+            &user_config_path,
+            "include: [helpers.yaml]\ndefault_language: lua\nrun_defaults:\n  fail_fast: true\n  order: alpha\n",
+        )?;
+        write(&config_path, "run_defaults:\n  order: topo\n")?;
+
+        let loaded_config = load_config_layers(
+            Some(&user_config_path),
+            &workspace_dir,
+            Some(&config_path),
+            true,
+        )?
+        .expect("must be present");
+        let run_defaults = loaded_config.config.run_defaults.expect("must be present");
+        assert_eq!(Some(true), run_defaults.fail_fast);
+        assert_eq!(Some(ProjectOrder::Topological), run_defaults.order);
+        assert_eq!(
+            Some(true),
+            loaded_config
+                .config
+                .lua_config
+                .expect("must be present")
+                .use_prelude
+        );
+        assert_eq!(
+            vec![user_config_path],
+            loaded_config.sources["run_defaults.fail_fast"]
+        );
+        assert_eq!(
+            vec![config_path],
+            loaded_config.sources["run_defaults.order"]
+        );
+
+        assert!(load_config_layers(None, &workspace_dir, None, true)?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn interpolate_config_values() -> Result<()> {
+        let mut config = from_str::<Config>(
+            r#"variables:
+  MIRROR: https://${MIRROR_HOST:-github.com}/org
+  PATHS:
+    - ${HOME}/bin
+excluded_projects: ["$${EXCLUDED}", "${EXCLUDED}"]
+dependencies:
+  aaa: ["${DEP}"]
+init_command:
+  script: print("${HOME}")
+"#,
+        )?;
+        interpolate_config(&mut config, |name| match name {
+            "HOME" => Some(String::from("/home/user")),
+            "EXCLUDED" => Some(String::from("bbb")),
+            "DEP" => Some(String::from("ccc")),
+            _ => None,
+        })?;
+
+        let variables = config.variables.expect("must be present");
+        assert_eq!(
+            "https://github.com/org",
+            variables["MIRROR"].as_str().expect("must be string")
+        );
+        assert_eq!(
+            "/home/user/bin",
+            variables["PATHS"][0].as_str().expect("must be string")
+        );
+        assert_eq!(
+            Some(vec![String::from("${EXCLUDED}"), String::from("bbb")]),
+            config.excluded_projects
+        );
+        assert_eq!(
+            vec![String::from("ccc")],
+            config.dependencies.expect("must be present")["aaa"]
+        );
+        assert_eq!(
+            "print(\"${HOME}\")",
+            config.init_command.expect("must be present").script
+        );
+
+        let mut config = from_str::<Config>("dependency_file: ${MISSING}\n")?;
+        assert_eq!(
+            "dependency_file: Environment variable MISSING is not set",
+            interpolate_config(&mut config, |_| None)
+                .expect_err("must fail")
+                .to_string()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_config_toml_and_json() -> Result<()> {
+        let toml = r#"excluded_projects = ["aaa"]
+
+[dependency_command]
+script = """
+local deps = { }
+return deps
+"""
+"#;
+        let config = parse_config(Path::new("rws-workspace.toml"), toml, true)?.0;
+        assert_eq!(Some(vec![String::from("aaa")]), config.excluded_projects);
+        assert!(config
+            .dependency_command
+            .expect("must be present")
+            .script
+            .contains("local deps"));
+
+        let json = r#"{"variables": {"KEY": ["one", 2]}, "strict_dependencies": true}"#;
+        let config = parse_config(Path::new("rws-workspace.json"), json, true)?.0;
+        assert_eq!(Some(true), config.strict_dependencies);
+        assert_eq!(
+            2,
+            config.variables.expect("must be present")["KEY"]
+                .as_sequence()
+                .expect("must be sequence")
+                .len()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_config_toml_and_json_error_locations() {
+        let e = parse_config(
+            Path::new("rws-workspace.toml"),
+            "excluded_projects = []\nstrict_dependencies = \"yes\"\n",
+            true,
+        )
+        .expect_err("must fail");
+        assert!(e.to_string().starts_with(
+            "rws-workspace.toml:2:23: invalid type: string \"yes\", expected a boolean"
+        ));
+
+        let e = parse_config(
+            Path::new("rws-workspace.json"),
+            "{\n  \"strict_dependencies\": \"yes\"\n}",
+            true,
+        )
+        .expect_err("must fail");
+        assert_eq!(
+            "rws-workspace.json:2:30: invalid type: string \"yes\", expected a boolean",
+            e.to_string()
+        );
+    }
+}
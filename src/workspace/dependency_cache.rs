@@ -0,0 +1,181 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2020-3 Richard Cook
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy of
+// this software and associated documentation files (the "Software"), to deal in
+// the Software without restriction, including without limitation the rights to
+// use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software is furnished to do so,
+// subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+// IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+// CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+//
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{create_dir_all, metadata, read_to_string, remove_file, write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+const DEPENDENCY_CACHE_FILE_NAME: &str = "dependency-cache.json";
+
+/// Results of dependency commands from previous runs
+pub struct DependencyCache {
+    path: PathBuf,
+    entries: HashMap<PathBuf, CacheEntry>,
+    changed: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct CacheEntry {
+    #[serde(rename = "key")]
+    key: String,
+
+    #[serde(rename = "files")]
+    files: Vec<FileStamp>,
+
+    #[serde(rename = "dependencies")]
+    dependencies: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct FileStamp {
+    #[serde(rename = "path")]
+    path: PathBuf,
+
+    #[serde(rename = "modified")]
+    modified: Option<u128>,
+}
+
+impl FileStamp {
+    fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            modified: metadata(path)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_nanos()),
+        }
+    }
+
+    fn is_current(&self) -> bool {
+        *self == Self::new(&self.path)
+    }
+}
+
+impl DependencyCache {
+    /// Load cache from state directory ignoring any unreadable cache
+    pub fn load(state_dir: &Path) -> Self {
+        let path = state_dir.join(DEPENDENCY_CACHE_FILE_NAME);
+        let entries = read_to_string(&path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            entries,
+            changed: false,
+        }
+    }
+
+    /// Delete cache from state directory returning whether it existed
+    pub fn clear(state_dir: &Path) -> Result<bool> {
+        let path = state_dir.join(DEPENDENCY_CACHE_FILE_NAME);
+        if !path.is_file() {
+            return Ok(false);
+        }
+        remove_file(path)?;
+        Ok(true)
+    }
+
+    /// Cached dependencies of project if key matches and files read are unchanged
+    pub fn get(&self, project_dir: &Path, key: &str) -> Option<Vec<String>> {
+        self.entries
+            .get(project_dir)
+            .filter(|e| e.key == key && e.files.iter().all(FileStamp::is_current))
+            .map(|e| e.dependencies.clone())
+    }
+
+    pub fn insert(
+        &mut self,
+        project_dir: &Path,
+        key: &str,
+        files: &[PathBuf],
+        dependencies: &[String],
+    ) {
+        _ = self.entries.insert(
+            project_dir.to_path_buf(),
+            CacheEntry {
+                key: String::from(key),
+                files: files.iter().map(|f| FileStamp::new(f)).collect(),
+                dependencies: dependencies.to_vec(),
+            },
+        );
+        self.changed = true;
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if !self.changed {
+            return Ok(());
+        }
+
+        if let Some(dir) = self.path.parent() {
+            create_dir_all(dir)?;
+        }
+        write(&self.path, serde_json::to_string_pretty(&self.entries)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DependencyCache;
+    use anyhow::Result;
+    use std::fs::write;
+    use std::path::{Path, PathBuf};
+    use tempfile::tempdir;
+
+    #[test]
+    fn basics() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let project_dir = Path::new("/ws/aaa");
+        let deps_path = temp_dir.path().join("_DEPS");
+        let missing_path = temp_dir.path().join("_MISSING");
+        write(&deps_path, "bbb")?;
+
+        let mut cache = DependencyCache::load(temp_dir.path());
+        assert_eq!(None, cache.get(project_dir, "key"));
+        cache.insert(
+            project_dir,
+            "key",
+            &[deps_path, missing_path.clone()],
+            &[String::from("bbb")],
+        );
+        cache.save()?;
+
+        let cache = DependencyCache::load(temp_dir.path());
+        assert_eq!(
+            Some(vec![String::from("bbb")]),
+            cache.get(project_dir, "key")
+        );
+        assert_eq!(None, cache.get(project_dir, "other-key"));
+        assert_eq!(None, cache.get(&PathBuf::from("/ws/bbb"), "key"));
+
+        write(&missing_path, "")?;
+        assert_eq!(None, cache.get(project_dir, "key"));
+
+        assert!(DependencyCache::clear(temp_dir.path())?);
+        assert!(!DependencyCache::clear(temp_dir.path())?);
+        Ok(())
+    }
+}
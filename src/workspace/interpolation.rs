@@ -0,0 +1,120 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2020-3 Richard Cook
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy of
+// this software and associated documentation files (the "Software"), to deal in
+// the Software without restriction, including without limitation the rights to
+// use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software is furnished to do so,
+// subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+// IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+// CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+//
+use anyhow::{anyhow, bail, Result};
+
+/// Expand "${VAR}" and "${VAR:-default}" references using given lookup where
+/// "$${" produces a literal "${" and default applies if variable is unset or empty
+pub fn interpolate<F>(s: &str, lookup: F) -> Result<String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut result = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(i) = rest.find('$') {
+        result.push_str(&rest[..i]);
+        rest = &rest[i..];
+
+        if let Some(r) = rest.strip_prefix("$${") {
+            result.push_str("${");
+            rest = r;
+            continue;
+        }
+
+        let Some(r) = rest.strip_prefix("${") else {
+            result.push('$');
+            rest = &rest[1..];
+            continue;
+        };
+
+        let end = r
+            .find('}')
+            .ok_or_else(|| anyhow!("Unterminated variable reference in \"{s}\""))?;
+        let (name, default) = match r[..end].split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (&r[..end], None),
+        };
+
+        if !is_valid_name(name) {
+            bail!("Invalid variable name \"{name}\" in \"{s}\"")
+        }
+
+        match (lookup(name), default) {
+            (Some(value), Some(default)) if value.is_empty() => result.push_str(default),
+            (Some(value), _) => result.push_str(&value),
+            (None, Some(default)) => result.push_str(default),
+            (None, None) => bail!("Environment variable {name} is not set"),
+        }
+        rest = &r[end + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::interpolate;
+    use anyhow::Result;
+    use rstest::rstest;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "HOST" => Some(String::from("mirror.example.com")),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    #[rstest]
+    #[case("plain", "plain")]
+    #[case("https://${HOST}/repo", "https://mirror.example.com/repo")]
+    #[case("${MISSING:-fallback}", "fallback")]
+    #[case("${EMPTY:-fallback}", "fallback")]
+    #[case("[${EMPTY}]", "[]")]
+    #[case("${HOST:-fallback}", "mirror.example.com")]
+    #[case("${MISSING:-}", "")]
+    #[case("$${HOST} costs $5", "${HOST} costs $5")]
+    fn interpolate_basics(#[case] input: &str, #[case] expected: &str) -> Result<()> {
+        assert_eq!(expected, interpolate(input, lookup)?);
+        Ok(())
+    }
+
+    #[rstest]
+    #[case("${MISSING}", "Environment variable MISSING is not set")]
+    #[case("${HOST", "Unterminated variable reference in \"${HOST\"")]
+    #[case("${1ABC}", "Invalid variable name \"1ABC\" in \"${1ABC}\"")]
+    fn interpolate_errors(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(
+            expected,
+            interpolate(input, lookup)
+                .expect_err("must fail")
+                .to_string()
+        );
+    }
+}
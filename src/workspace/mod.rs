@@ -19,13 +19,22 @@
 // IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
 // CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 //
+mod cargo_dependencies;
 mod config;
+mod config_file;
+mod dependency_cache;
+mod interpolation;
 mod plan;
+mod project_filter;
 mod session;
 mod topo_order;
 
 pub use self::config::{
-    Command, Config, DependencySource, Language, LanguageConfig, StaticDependencies, Variables,
+    Command, Config, DependenciesMode, DependencySource, Language, LanguageConfig,
+    StaticDependencies, Variables,
 };
+pub use self::config_file::{get_config_problems, get_config_schema, load_config_layers};
+pub use self::dependency_cache::DependencyCache;
 pub use self::plan::Plan;
+pub use self::project_filter::ProjectFilter;
 pub use self::session::{Session, WORKSPACE_CONFIG_FILE_NAME};
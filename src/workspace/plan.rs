@@ -19,13 +19,17 @@
 // IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
 // CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 //
+use super::cargo_dependencies::CargoDependencies;
+use super::dependency_cache::DependencyCache;
 use super::session::Session;
 use super::topo_order::compute_topo_order;
-use crate::scripting::eval_script_command;
-use crate::workspace::{Command, DependencySource, StaticDependencies};
-use anyhow::{anyhow, Result};
+use crate::scripting::{eval_script_command, get_script_command_key, track_file_access};
+use crate::workspace::{Command, Config, DependenciesMode, DependencySource, StaticDependencies};
+use anyhow::{anyhow, bail, Result};
+use colored::Colorize;
 use joatmon::WorkingDirectory;
-use std::collections::HashSet;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::ffi::OsStr;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -36,11 +40,40 @@ pub struct Plan {
     pub project_dirs_alpha: Vec<PathBuf>,
     /// Project directories in topological order
     pub project_dirs_topo: Option<Vec<PathBuf>>,
+    /// Predecessors of each project directory
+    pub precs: Option<HashMap<PathBuf, Vec<PathBuf>>>,
 }
 
 impl Plan {
-    /// Create a plan from a workspace
+    /// Create a plan from a workspace reporting dependency graph problems
+    /// as warnings or, if `strict_dependencies` is set, as errors
     pub fn new(session: &Session) -> Result<Self> {
+        let (plan, problems) = Self::check(session)?;
+        let Some(plan) = plan else {
+            bail!("Invalid dependency graph:\n  {}", problems.join("\n  "));
+        };
+        if !problems.is_empty() {
+            if session
+                .config
+                .as_ref()
+                .and_then(|c| c.strict_dependencies)
+                .unwrap_or(false)
+            {
+                bail!("Invalid dependency graph:\n  {}", problems.join("\n  "));
+            }
+
+            for problem in &problems {
+                eprintln!("{}", format!("Warning: {problem}").yellow());
+            }
+        }
+        Ok(plan)
+    }
+
+    /// Create a plan from a workspace along with any problems found in its
+    /// dependency graph: dependencies on projects that do not exist or are
+    /// excluded are removed from the plan and no plan is created if the
+    /// graph contains a cycle
+    pub fn check(session: &Session) -> Result<(Option<Self>, Vec<String>)> {
         let exclude_project_dirs = session
             .config
             .as_ref()
@@ -53,26 +86,382 @@ impl Plan {
         let project_dirs_alpha =
             Self::get_project_dirs_alpha(&session.workspace_dir, &exclude_project_dirs)?;
 
-        let project_dirs_topo = match &session.config {
-            Some(c) => match &c.dependency_source {
-                Some(DependencySource::Static(static_dependencies)) => {
-                    Some(compute_topo_order(&project_dirs_alpha, |project_dir| {
-                        Self::get_precs_from_config_hash(static_dependencies, session, project_dir)
-                    })?)
-                }
-                Some(DependencySource::Command(command)) => {
-                    Some(compute_topo_order(&project_dirs_alpha, |project_dir| {
-                        Self::get_precs_from_script_command(command, session, project_dir)
-                    })?)
+        let mut problems = Vec::new();
+        let mut precs = None;
+        for dependency_source in session
+            .config
+            .as_ref()
+            .map(Config::dependency_sources)
+            .unwrap_or_default()
+        {
+            let (source_precs, replace) = Self::get_source_precs(
+                &dependency_source,
+                session,
+                &project_dirs_alpha,
+                &exclude_project_dirs,
+                &mut problems,
+            )?;
+            precs = Some(match precs {
+                Some(precs) => Self::merge_precs(precs, source_precs, replace),
+                None => source_precs,
+            });
+        }
+
+        if let Some(precs) = &mut precs {
+            problems.extend(Self::validate_precs(
+                precs,
+                &session.workspace_dir,
+                &project_dirs_alpha,
+                &exclude_project_dirs,
+            ));
+        }
+
+        let project_dirs_topo = match &precs {
+            Some(precs) => match compute_topo_order(&project_dirs_alpha, |project_dir| {
+                Ok(precs.get(project_dir).cloned().unwrap_or_default())
+            }) {
+                Ok(project_dirs_topo) => Some(project_dirs_topo),
+                Err(e) => {
+                    problems.push(e.to_string());
+                    return Ok((None, problems));
                 }
-                None => None,
             },
             None => None,
         };
-        Ok(Self {
+
+        Ok((
+            Some(Self {
+                project_dirs_alpha,
+                project_dirs_topo,
+                precs,
+            }),
+            problems,
+        ))
+    }
+
+    fn validate_static_dependencies(
+        static_dependencies: &StaticDependencies,
+        session: &Session,
+        project_dirs: &[PathBuf],
+        excluded_project_dirs: &HashSet<PathBuf>,
+    ) -> Vec<String> {
+        let mut project_names = static_dependencies
+            .keys()
+            .filter(|project_name| {
+                let project_dir = session.workspace_dir.join(project_name);
+                !project_dirs.contains(&project_dir)
+                    && !excluded_project_dirs.contains(&project_dir)
+            })
+            .collect::<Vec<_>>();
+        project_names.sort();
+        project_names
+            .into_iter()
+            .map(|project_name| format!("Dependencies declared for unknown project {project_name}"))
+            .collect()
+    }
+
+    // Removes predecessors that are not projects in the plan
+    fn validate_precs(
+        precs: &mut HashMap<PathBuf, Vec<PathBuf>>,
+        workspace_dir: &Path,
+        project_dirs: &[PathBuf],
+        excluded_project_dirs: &HashSet<PathBuf>,
+    ) -> Vec<String> {
+        let get_project_name = |d: &Path| {
+            d.strip_prefix(workspace_dir)
+                .unwrap_or(d)
+                .display()
+                .to_string()
+        };
+
+        let mut problems = Vec::new();
+        for project_dir in project_dirs {
+            let Some(ps) = precs.get_mut(project_dir) else {
+                continue;
+            };
+            ps.retain(|p| {
+                if project_dirs.contains(p) {
+                    return true;
+                }
+
+                problems.push(format!(
+                    "Project {} depends on {} project {}",
+                    get_project_name(project_dir),
+                    if excluded_project_dirs.contains(p) {
+                        "excluded"
+                    } else {
+                        "unknown"
+                    },
+                    get_project_name(p)
+                ));
+                false
+            });
+        }
+        problems
+    }
+
+    // Dependencies from a single source and whether they replace those from
+    // previous sources
+    fn get_source_precs(
+        dependency_source: &DependencySource,
+        session: &Session,
+        project_dirs_alpha: &[PathBuf],
+        exclude_project_dirs: &HashSet<PathBuf>,
+        problems: &mut Vec<String>,
+    ) -> Result<(HashMap<PathBuf, Vec<PathBuf>>, bool)> {
+        Ok(match dependency_source {
+            DependencySource::Static(static_dependencies) => {
+                problems.extend(Self::validate_static_dependencies(
+                    static_dependencies,
+                    session,
+                    project_dirs_alpha,
+                    exclude_project_dirs,
+                ));
+
+                // Only projects with static entries override computed dependencies
+                let declared_project_dirs = project_dirs_alpha
+                    .iter()
+                    .filter(|d| {
+                        d.file_name()
+                            .and_then(OsStr::to_str)
+                            .is_some_and(|n| static_dependencies.contains_key(n))
+                    })
+                    .cloned()
+                    .collect::<Vec<_>>();
+                (
+                    Self::get_precs(&declared_project_dirs, |project_dir| {
+                        Self::get_precs_from_config_hash(static_dependencies, session, project_dir)
+                    })?,
+                    session
+                        .config
+                        .as_ref()
+                        .and_then(|c| c.dependencies_mode)
+                        .unwrap_or_default()
+                        == DependenciesMode::Replace,
+                )
+            }
+            DependencySource::Command(command) => {
+                let cache = RefCell::new(if session.use_cache {
+                    Some(DependencyCache::load(&session.state_dir()))
+                } else {
+                    None
+                });
+                let key = get_script_command_key(session, command)?;
+                let precs = Self::get_precs(project_dirs_alpha, |project_dir| {
+                    Self::get_precs_from_script_command(command, session, project_dir, &key, &cache)
+                })?;
+                if let Some(cache) = cache.into_inner() {
+                    cache.save()?;
+                }
+                (precs, false)
+            }
+            DependencySource::File(file_name) => (
+                Self::get_precs(project_dirs_alpha, |project_dir| {
+                    Self::get_precs_from_dependency_file(file_name, session, project_dir)
+                })?,
+                false,
+            ),
+            DependencySource::Cargo(cargo_config) => {
+                let mut candidate_dirs = project_dirs_alpha.to_vec();
+                candidate_dirs.extend(exclude_project_dirs.iter().cloned());
+                let cargo_dependencies = CargoDependencies::new(cargo_config, &candidate_dirs);
+                (
+                    Self::get_precs(project_dirs_alpha, |project_dir| {
+                        cargo_dependencies.get_precs(project_dir)
+                    })?,
+                    false,
+                )
+            }
+        })
+    }
+
+    fn merge_precs(
+        mut precs: HashMap<PathBuf, Vec<PathBuf>>,
+        source_precs: HashMap<PathBuf, Vec<PathBuf>>,
+        replace: bool,
+    ) -> HashMap<PathBuf, Vec<PathBuf>> {
+        for (project_dir, ps) in source_precs {
+            let e = precs.entry(project_dir).or_default();
+            if replace {
+                *e = ps;
+            } else {
+                for p in ps {
+                    if !e.contains(&p) {
+                        e.push(p);
+                    }
+                }
+            }
+        }
+        precs
+    }
+
+    fn get_precs<F>(
+        project_dirs: &[PathBuf],
+        get_precs: F,
+    ) -> Result<HashMap<PathBuf, Vec<PathBuf>>>
+    where
+        F: Fn(&Path) -> Result<Vec<PathBuf>>,
+    {
+        project_dirs
+            .iter()
+            .map(|project_dir| get_precs(project_dir).map(|precs| (project_dir.clone(), precs)))
+            .collect()
+    }
+
+    /// Name of project in given project directory
+    pub fn get_project_name(project_dir: &Path) -> String {
+        project_dir
+            .file_name()
+            .map_or_else(|| project_dir.to_string_lossy(), OsStr::to_string_lossy)
+            .to_string()
+    }
+
+    /// Find project directory with given project name
+    pub fn find_project_dir(&self, project_name: &str) -> Result<PathBuf> {
+        self.project_dirs_alpha
+            .iter()
+            .find(|d| d.file_name().and_then(OsStr::to_str) == Some(project_name))
+            .cloned()
+            .ok_or_else(|| anyhow!("Project {project_name} not found"))
+    }
+
+    /// Direct predecessors of project directory
+    pub fn get_project_precs(&self, project_dir: &Path) -> Vec<PathBuf> {
+        self.precs
+            .as_ref()
+            .and_then(|precs| precs.get(project_dir))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Direct successors of project directory
+    pub fn get_project_succs(&self, project_dir: &Path) -> Vec<PathBuf> {
+        let mut succs = self
+            .precs
+            .as_ref()
+            .map(|precs| {
+                precs
+                    .iter()
+                    .filter(|(_, ps)| ps.iter().any(|p| p == project_dir))
+                    .map(|(d, _)| d.clone())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        succs.sort();
+        succs
+    }
+
+    /// Project directory and all project directories it transitively depends on
+    pub fn get_deps_closure(&self, project_dir: &Path) -> HashSet<PathBuf> {
+        Self::get_closure(project_dir, |d| self.get_project_precs(d))
+    }
+
+    /// Project directory and all project directories transitively depending on it
+    pub fn get_dependents_closure(&self, project_dir: &Path) -> HashSet<PathBuf> {
+        Self::get_closure(project_dir, |d| self.get_project_succs(d))
+    }
+
+    /// Shortest chain of dependencies leading from one project directory to another
+    pub fn get_shortest_path(&self, from_dir: &Path, to_dir: &Path) -> Option<Vec<PathBuf>> {
+        let mut parents = HashMap::<PathBuf, PathBuf>::new();
+        let mut queue = VecDeque::from([from_dir.to_path_buf()]);
+        while let Some(d) = queue.pop_front() {
+            if d == to_dir {
+                let mut path = vec![d.clone()];
+                let mut current = &d;
+                while let Some(parent) = parents.get(current) {
+                    path.push(parent.clone());
+                    current = parent;
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            let mut precs = self.get_project_precs(&d);
+            precs.sort();
+            for prec in precs {
+                if prec != from_dir && !parents.contains_key(&prec) {
+                    _ = parents.insert(prec.clone(), d.clone());
+                    queue.push_back(prec);
+                }
+            }
+        }
+        None
+    }
+
+    fn get_closure<F>(project_dir: &Path, get_next: F) -> HashSet<PathBuf>
+    where
+        F: Fn(&Path) -> Vec<PathBuf>,
+    {
+        let mut closure = HashSet::new();
+        let mut stack = vec![project_dir.to_path_buf()];
+        while let Some(d) = stack.pop() {
+            if closure.insert(d.clone()) {
+                stack.extend(get_next(&d));
+            }
+        }
+        closure
+    }
+
+    /// Restrict plan to project directories satisfying predicate while
+    /// preserving ordering constraints that pass through excluded projects
+    pub fn filter<P>(&self, predicate: P) -> Self
+    where
+        P: Fn(&Path) -> bool,
+    {
+        let project_dirs_alpha = self
+            .project_dirs_alpha
+            .iter()
+            .filter(|d| predicate(d))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let project_dirs_topo = self.project_dirs_topo.as_ref().map(|ds| {
+            ds.iter()
+                .filter(|d| predicate(d))
+                .cloned()
+                .collect::<Vec<_>>()
+        });
+
+        let precs = self.precs.as_ref().map(|precs| {
+            project_dirs_alpha
+                .iter()
+                .map(|d| (d.clone(), Self::get_filtered_precs(precs, d, &predicate)))
+                .collect::<HashMap<_, _>>()
+        });
+
+        Self {
             project_dirs_alpha,
             project_dirs_topo,
-        })
+            precs,
+        }
+    }
+
+    fn get_filtered_precs<P>(
+        precs: &HashMap<PathBuf, Vec<PathBuf>>,
+        project_dir: &Path,
+        predicate: &P,
+    ) -> Vec<PathBuf>
+    where
+        P: Fn(&Path) -> bool,
+    {
+        let mut filtered_precs = Vec::new();
+        let mut visited = HashSet::new();
+        let mut stack = precs.get(project_dir).cloned().unwrap_or_default();
+        while let Some(prec) = stack.pop() {
+            if !visited.insert(prec.clone()) {
+                continue;
+            }
+
+            if predicate(&prec) {
+                filtered_precs.push(prec);
+            } else if let Some(ps) = precs.get(&prec) {
+                stack.extend(ps.iter().cloned());
+            }
+        }
+
+        filtered_precs.sort();
+        filtered_precs
     }
 
     fn get_project_dirs_alpha(
@@ -124,13 +513,274 @@ impl Plan {
         command: &Command,
         session: &Session,
         project_dir: &Path,
+        key: &str,
+        cache: &RefCell<Option<DependencyCache>>,
     ) -> Result<Vec<PathBuf>> {
-        let working_dir = WorkingDirectory::change(project_dir)?;
-        let deps = eval_script_command::<Vec<String>>(session, command)?;
-        drop(working_dir);
+        let cached_deps = cache
+            .borrow()
+            .as_ref()
+            .and_then(|c| c.get(project_dir, key));
+        let deps = if let Some(deps) = cached_deps {
+            deps
+        } else {
+            let working_dir = WorkingDirectory::change(project_dir)?;
+            let (deps, files) =
+                track_file_access(|| eval_script_command::<Vec<String>>(session, command));
+            drop(working_dir);
+            let deps = deps?;
+            if let Some(c) = cache.borrow_mut().as_mut() {
+                c.insert(project_dir, key, &files, &deps);
+            }
+            deps
+        };
         Ok(deps
             .into_iter()
             .map(|x| session.workspace_dir.join(x))
             .collect::<Vec<_>>())
     }
+
+    fn get_precs_from_dependency_file(
+        file_name: &str,
+        session: &Session,
+        project_dir: &Path,
+    ) -> Result<Vec<PathBuf>> {
+        let path = project_dir.join(file_name);
+        if !path.is_file() {
+            return Ok(Vec::new());
+        }
+
+        Ok(Self::parse_dependency_lines(&fs::read_to_string(path)?)
+            .into_iter()
+            .map(|x| session.workspace_dir.join(x))
+            .collect::<Vec<_>>())
+    }
+
+    // One project name per line: "#" starts a comment and blank lines are ignored
+    fn parse_dependency_lines(s: &str) -> Vec<&str> {
+        s.lines()
+            .map(|line| line.split_once('#').map_or(line, |(s, _)| s).trim())
+            .filter(|line| !line.is_empty())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Plan;
+    use crate::workspace::Session;
+    use anyhow::Result;
+    use std::collections::{HashMap, HashSet};
+    use std::fs::{create_dir_all, write};
+    use std::path::{Path, PathBuf};
+    use tempfile::tempdir;
+
+    fn make_plan() -> Plan {
+        Plan {
+            project_dirs_alpha: vec![
+                PathBuf::from("aaa"),
+                PathBuf::from("bbb"),
+                PathBuf::from("ccc"),
+                PathBuf::from("ddd"),
+            ],
+            project_dirs_topo: Some(vec![
+                PathBuf::from("ddd"),
+                PathBuf::from("ccc"),
+                PathBuf::from("bbb"),
+                PathBuf::from("aaa"),
+            ]),
+            precs: Some(HashMap::from([
+                (PathBuf::from("aaa"), vec![PathBuf::from("bbb")]),
+                (PathBuf::from("bbb"), vec![PathBuf::from("ccc")]),
+                (PathBuf::from("ccc"), vec![PathBuf::from("ddd")]),
+                (PathBuf::from("ddd"), vec![]),
+            ])),
+        }
+    }
+
+    #[test]
+    fn closures() {
+        let plan = make_plan();
+        assert_eq!(
+            HashSet::from([
+                PathBuf::from("bbb"),
+                PathBuf::from("ccc"),
+                PathBuf::from("ddd")
+            ]),
+            plan.get_deps_closure(Path::new("bbb"))
+        );
+        assert_eq!(
+            HashSet::from([PathBuf::from("aaa"), PathBuf::from("bbb")]),
+            plan.get_dependents_closure(Path::new("bbb"))
+        );
+        assert_eq!(
+            HashSet::from([PathBuf::from("aaa")]),
+            plan.get_deps_closure(Path::new("aaa"))
+                .intersection(&plan.get_dependents_closure(Path::new("aaa")))
+                .cloned()
+                .collect()
+        );
+    }
+
+    #[test]
+    fn shortest_path() {
+        let mut plan = make_plan();
+        if let Some(precs) = &mut plan.precs {
+            precs
+                .get_mut(Path::new("aaa"))
+                .expect("must be present")
+                .push(PathBuf::from("ccc"));
+        }
+
+        assert_eq!(
+            Some(vec![
+                PathBuf::from("aaa"),
+                PathBuf::from("ccc"),
+                PathBuf::from("ddd")
+            ]),
+            plan.get_shortest_path(Path::new("aaa"), Path::new("ddd"))
+        );
+        assert_eq!(
+            Some(vec![PathBuf::from("bbb")]),
+            plan.get_shortest_path(Path::new("bbb"), Path::new("bbb"))
+        );
+        assert_eq!(
+            None,
+            plan.get_shortest_path(Path::new("ddd"), Path::new("aaa"))
+        );
+    }
+
+    #[test]
+    fn parse_dependency_lines() {
+        assert_eq!(
+            vec!["bbb", "ccc", "ddd"],
+            Plan::parse_dependency_lines(
+                "# Dependencies\n\nbbb\n  ccc  \n\t# Comment\nddd # Trailing comment\n"
+            )
+        );
+        assert!(Plan::parse_dependency_lines("").is_empty());
+    }
+
+    #[test]
+    fn merge_precs() {
+        let precs = HashMap::from([
+            (PathBuf::from("aaa"), vec![PathBuf::from("ccc")]),
+            (PathBuf::from("bbb"), vec![PathBuf::from("ccc")]),
+        ]);
+        let source_precs = HashMap::from([
+            (
+                PathBuf::from("aaa"),
+                vec![PathBuf::from("bbb"), PathBuf::from("ccc")],
+            ),
+            (PathBuf::from("ccc"), vec![PathBuf::from("ddd")]),
+        ]);
+
+        assert_eq!(
+            HashMap::from([
+                (
+                    PathBuf::from("aaa"),
+                    vec![PathBuf::from("ccc"), PathBuf::from("bbb")]
+                ),
+                (PathBuf::from("bbb"), vec![PathBuf::from("ccc")]),
+                (PathBuf::from("ccc"), vec![PathBuf::from("ddd")]),
+            ]),
+            Plan::merge_precs(precs.clone(), source_precs.clone(), false)
+        );
+        assert_eq!(
+            HashMap::from([
+                (
+                    PathBuf::from("aaa"),
+                    vec![PathBuf::from("bbb"), PathBuf::from("ccc")]
+                ),
+                (PathBuf::from("bbb"), vec![PathBuf::from("ccc")]),
+                (PathBuf::from("ccc"), vec![PathBuf::from("ddd")]),
+            ]),
+            Plan::merge_precs(precs, source_precs, true)
+        );
+    }
+
+    #[test]
+    fn filter_basics() {
+        let plan = make_plan();
+        let filtered_plan = plan.filter(|d| d != Path::new("bbb") && d != Path::new("ccc"));
+
+        assert_eq!(
+            vec![PathBuf::from("aaa"), PathBuf::from("ddd")],
+            filtered_plan.project_dirs_alpha
+        );
+        assert_eq!(
+            Some(vec![PathBuf::from("ddd"), PathBuf::from("aaa")]),
+            filtered_plan.project_dirs_topo
+        );
+        assert_eq!(
+            Some(HashMap::from([
+                (PathBuf::from("aaa"), vec![PathBuf::from("ddd")]),
+                (PathBuf::from("ddd"), vec![]),
+            ])),
+            filtered_plan.precs
+        );
+    }
+
+    #[test]
+    fn check_reports_problems_with_cycle() -> Result<()> {
+        let temp_dir = tempdir()?;
+        for project_name in ["aaa", "bbb"] {
+            create_dir_all(temp_dir.path().join(project_name).join(".git"))?;
+        }
+        write(
+            temp_dir.path().join("rws-workspace.yaml"),
+            "dependencies:\n  aaa: [bbb, zzz]\n  bbb: [aaa]\n",
+        )?;
+        let session = Session::new(temp_dir.path(), Some(temp_dir.path()), None, false, true)?;
+
+        let (plan, problems) = Plan::check(&session)?;
+        assert!(plan.is_none());
+        assert_eq!(
+            vec![
+                "Project aaa depends on unknown project zzz",
+                "Dependency cycle detected: aaa -> bbb -> aaa"
+            ],
+            problems
+        );
+        assert!(Plan::new(&session).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn validate_precs_basics() {
+        let project_dirs = vec![PathBuf::from("/ws/aaa"), PathBuf::from("/ws/bbb")];
+        let excluded_project_dirs = HashSet::from([PathBuf::from("/ws/ccc")]);
+        let mut precs = HashMap::from([
+            (
+                PathBuf::from("/ws/aaa"),
+                vec![
+                    PathBuf::from("/ws/bbb"),
+                    PathBuf::from("/ws/ccc"),
+                    PathBuf::from("/ws/zzz"),
+                ],
+            ),
+            (PathBuf::from("/ws/bbb"), vec![]),
+        ]);
+
+        let problems = Plan::validate_precs(
+            &mut precs,
+            Path::new("/ws"),
+            &project_dirs,
+            &excluded_project_dirs,
+        );
+
+        assert_eq!(
+            vec![
+                "Project aaa depends on excluded project ccc",
+                "Project aaa depends on unknown project zzz"
+            ],
+            problems
+        );
+        assert_eq!(
+            HashMap::from([
+                (PathBuf::from("/ws/aaa"), vec![PathBuf::from("/ws/bbb")]),
+                (PathBuf::from("/ws/bbb"), vec![]),
+            ]),
+            precs
+        );
+    }
 }
@@ -0,0 +1,115 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2020-3 Richard Cook
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy of
+// this software and associated documentation files (the "Software"), to deal in
+// the Software without restriction, including without limitation the rights to
+// use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software is furnished to do so,
+// subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+// IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+// CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+//
+use anyhow::Result;
+use regex::{escape, Regex};
+use std::ffi::OsStr;
+use std::path::Path;
+
+const REGEX_PREFIX: &str = "re:";
+
+/// Selects projects by name using globs or regular expressions
+#[derive(Debug)]
+pub struct ProjectFilter {
+    includes: Vec<Regex>,
+    excludes: Vec<Regex>,
+}
+
+impl ProjectFilter {
+    /// Constructor: patterns prefixed with "re:" are regular expressions,
+    /// all others are globs supporting "*" and "?" wildcards
+    pub fn new(includes: &[String], excludes: &[String]) -> Result<Self> {
+        Ok(Self {
+            includes: includes
+                .iter()
+                .map(|p| Self::compile(p))
+                .collect::<Result<_>>()?,
+            excludes: excludes
+                .iter()
+                .map(|p| Self::compile(p))
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    pub fn matches(&self, project_dir: &Path) -> bool {
+        let Some(project_name) = project_dir.file_name().and_then(OsStr::to_str) else {
+            return false;
+        };
+
+        (self.includes.is_empty() || self.includes.iter().any(|r| r.is_match(project_name)))
+            && !self.excludes.iter().any(|r| r.is_match(project_name))
+    }
+
+    fn compile(pattern: &str) -> Result<Regex> {
+        Ok(match pattern.strip_prefix(REGEX_PREFIX) {
+            Some(s) => Regex::new(s)?,
+            None => Regex::new(&Self::glob_to_regex(pattern))?,
+        })
+    }
+
+    fn glob_to_regex(glob: &str) -> String {
+        let mut s = String::from("^");
+        for c in glob.chars() {
+            match c {
+                '*' => s.push_str(".*"),
+                '?' => s.push('.'),
+                _ => s.push_str(&escape(c.encode_utf8(&mut [0; 4]))),
+            }
+        }
+        s.push('$');
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProjectFilter;
+    use anyhow::Result;
+    use rstest::rstest;
+    use std::path::Path;
+
+    #[rstest]
+    #[case(true, &[], &[], "aaa")]
+    #[case(true, &["a*"], &[], "aaa")]
+    #[case(false, &["a*"], &[], "bbb")]
+    #[case(true, &["a?c"], &[], "abc")]
+    #[case(false, &["a?c"], &[], "abbc")]
+    #[case(false, &["a.c"], &[], "abc")]
+    #[case(true, &["re:^a.c$"], &[], "abc")]
+    #[case(true, &["zzz", "b*"], &[], "bbb")]
+    #[case(false, &[], &["b*"], "bbb")]
+    #[case(true, &[], &["b*"], "aaa")]
+    #[case(false, &["*"], &["lib-*"], "lib-foo")]
+    fn matches(
+        #[case] expected_result: bool,
+        #[case] includes: &[&str],
+        #[case] excludes: &[&str],
+        #[case] project_name: &str,
+    ) -> Result<()> {
+        let to_strings = |xs: &[&str]| xs.iter().map(|x| String::from(*x)).collect::<Vec<_>>();
+        let filter = ProjectFilter::new(&to_strings(includes), &to_strings(excludes))?;
+        assert_eq!(
+            expected_result,
+            filter.matches(&Path::new("workspace").join(project_name))
+        );
+        Ok(())
+    }
+}
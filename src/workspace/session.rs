@@ -19,15 +19,24 @@
 // IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
 // CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 //
-use super::config::Config;
-use anyhow::{anyhow, Result};
-use joatmon::{find_sentinel_file, read_yaml_file};
+use super::config::{Config, ConfigSources};
+use super::config_file::load_config_layers;
+use anyhow::{anyhow, bail, Result};
+use colored::Colorize;
+use dirs::config_dir;
 use lazy_static::lazy_static;
 use std::env;
 use std::path::{Path, PathBuf};
 
 lazy_static! {
     pub static ref WORKSPACE_CONFIG_FILE_NAME: PathBuf = PathBuf::from("rws-workspace.yaml");
+    pub static ref WORKSPACE_CONFIG_FILE_NAMES: Vec<PathBuf> = vec![
+        WORKSPACE_CONFIG_FILE_NAME.clone(),
+        PathBuf::from("rws-workspace.toml"),
+        PathBuf::from("rws-workspace.json"),
+    ];
+    pub static ref WORKSPACE_STATE_DIR_NAME: PathBuf = PathBuf::from(".rws");
+    pub static ref USER_CONFIG_FILE_NAME: PathBuf = PathBuf::from("rws").join("config.yaml");
 }
 
 /// Workspace information derived from file system and configuration file
@@ -39,8 +48,14 @@ pub struct Session {
     pub workspace_dir: PathBuf,
     /// Configuration path
     pub config_path: Option<PathBuf>,
+    /// User configuration path
+    pub user_config_path: Option<PathBuf>,
     /// Configuration
     pub config: Option<Config>,
+    /// Files that contributed each configuration value
+    pub config_sources: ConfigSources,
+    /// Whether to use cached dependency command results
+    pub use_cache: bool,
 }
 
 impl Session {
@@ -49,66 +64,132 @@ impl Session {
         cwd: &Path,
         workspace_dir_opt: Option<&Path>,
         config_path_opt: Option<&Path>,
+        use_cache: bool,
+        strict_config: bool,
     ) -> Result<Self> {
-        match (workspace_dir_opt, config_path_opt) {
-            (Some(workspace_dir), Some(config_path)) => {
-                Self::known(cwd, workspace_dir, Some(config_path))
+        let (workspace_dir, config_path) = Self::locate(workspace_dir_opt, config_path_opt)?;
+        let user_config_path = Self::user_config_path();
+        let (config, config_sources) = match load_config_layers(
+            user_config_path.as_deref(),
+            &workspace_dir,
+            config_path.as_deref(),
+            strict_config,
+        )? {
+            Some(loaded_config) => {
+                for warning in loaded_config.warnings {
+                    eprintln!("{}", format!("Warning: {warning}").yellow());
+                }
+                (Some(loaded_config.config), loaded_config.sources)
             }
-            (Some(workspace_dir), None) => {
-                let p = workspace_dir.join(&*WORKSPACE_CONFIG_FILE_NAME);
-                Self::known(cwd, workspace_dir, if p.exists() { Some(&p) } else { None })
+            None => (None, ConfigSources::new()),
+        };
+        Ok(Self {
+            cwd: cwd.to_path_buf(),
+            workspace_dir,
+            config_path,
+            user_config_path,
+            config,
+            config_sources,
+            use_cache,
+        })
+    }
+
+    /// Determine workspace directory and configuration path without reading configuration
+    pub fn locate(
+        workspace_dir_opt: Option<&Path>,
+        config_path_opt: Option<&Path>,
+    ) -> Result<(PathBuf, Option<PathBuf>)> {
+        Ok(match (workspace_dir_opt, config_path_opt) {
+            (Some(workspace_dir), Some(config_path)) => {
+                (workspace_dir.to_path_buf(), Some(config_path.to_path_buf()))
             }
-            (None, Some(config_path)) => Self::known(
-                cwd,
+            (Some(workspace_dir), None) => (
+                workspace_dir.to_path_buf(),
+                Self::find_config_file(workspace_dir)?,
+            ),
+            (None, Some(config_path)) => (
                 config_path
-                    .to_path_buf()
                     .parent()
-                    .ok_or_else(|| anyhow!("Invalid config path"))?,
-                Some(config_path),
+                    .ok_or_else(|| anyhow!("Invalid config path"))?
+                    .to_path_buf(),
+                Some(config_path.to_path_buf()),
             ),
-            (None, None) => Self::find(cwd, &env::current_dir()?),
-        }
+            (None, None) => {
+                let search_dir = env::current_dir()?;
+                for dir in search_dir.ancestors().take(5) {
+                    if let Some(config_path) = Self::find_config_file(dir)? {
+                        return Ok((dir.to_path_buf(), Some(config_path)));
+                    }
+                }
+                (search_dir, None)
+            }
+        })
     }
 
-    fn known(cwd: &Path, workspace_dir: &Path, config_path_opt: Option<&Path>) -> Result<Self> {
-        match config_path_opt {
-            Some(config_path) => Ok(Self {
-                cwd: cwd.to_path_buf(),
-                workspace_dir: workspace_dir.to_path_buf(),
-                config_path: Some(config_path.to_path_buf()),
-                config: Some(read_yaml_file(config_path)?),
-            }),
-            None => Ok(Self {
-                cwd: cwd.to_path_buf(),
-                workspace_dir: workspace_dir.to_path_buf(),
-                config_path: None,
-                config: None,
-            }),
+    /// Workspace configuration file in given directory in any supported format
+    pub fn find_config_file(dir: &Path) -> Result<Option<PathBuf>> {
+        let mut config_paths = WORKSPACE_CONFIG_FILE_NAMES
+            .iter()
+            .map(|n| dir.join(n))
+            .filter(|p| p.is_file())
+            .collect::<Vec<_>>();
+        if config_paths.len() > 1 {
+            let file_names = config_paths
+                .iter()
+                .filter_map(|p| p.file_name())
+                .map(|n| n.to_string_lossy())
+                .collect::<Vec<_>>();
+            bail!(
+                "Multiple workspace configuration files found in {}: {}",
+                dir.display(),
+                file_names.join(", ")
+            )
         }
+        Ok(config_paths.pop())
     }
 
-    fn find(cwd: &Path, search_dir: &Path) -> Result<Self> {
-        Ok(
-            match find_sentinel_file(&WORKSPACE_CONFIG_FILE_NAME, search_dir, Some(5)) {
-                Some(config_path) => {
-                    let config = read_yaml_file(&config_path)?;
-                    Self {
-                        cwd: cwd.to_path_buf(),
-                        workspace_dir: config_path
-                            .parent()
-                            .ok_or_else(|| anyhow!("Failed to obtain workspace directory"))?
-                            .to_path_buf(),
-                        config_path: Some(config_path),
-                        config: Some(config),
-                    }
-                }
-                None => Self {
-                    cwd: cwd.to_path_buf(),
-                    workspace_dir: search_dir.to_path_buf(),
-                    config_path: None,
-                    config: None,
-                },
-            },
-        )
+    /// Path to user configuration file if it exists
+    pub fn user_config_path() -> Option<PathBuf> {
+        config_dir()
+            .map(|d| d.join(&*USER_CONFIG_FILE_NAME))
+            .filter(|p| p.is_file())
+    }
+
+    /// Directory in which to persist workspace state
+    pub fn state_dir(&self) -> PathBuf {
+        self.workspace_dir.join(&*WORKSPACE_STATE_DIR_NAME)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Session;
+    use anyhow::Result;
+    use std::fs::write;
+    use tempfile::tempdir;
+
+    #[test]
+    fn find_config_file() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let dir = temp_dir.path();
+        assert_eq!(None, Session::find_config_file(dir)?);
+
+        write(dir.join("rws-workspace.toml"), "")?;
+        assert_eq!(
+            Some(dir.join("rws-workspace.toml")),
+            Session::find_config_file(dir)?
+        );
+
+        write(dir.join("rws-workspace.json"), "{}")?;
+        assert_eq!(
+            format!(
+                "Multiple workspace configuration files found in {}: rws-workspace.toml, rws-workspace.json",
+                dir.display()
+            ),
+            Session::find_config_file(dir)
+                .expect_err("must fail")
+                .to_string()
+        );
+        Ok(())
     }
 }
@@ -19,32 +19,46 @@
 // IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
 // CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 //
-use anyhow::Result;
+use super::plan::Plan;
+use anyhow::{bail, Result};
+use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
 use std::ops::Deref;
+use std::path::Path;
 use topological_sort::TopologicalSort;
 
 pub fn compute_topo_order<T, F>(targets: &[T], get_precs: F) -> Result<Vec<T>>
 where
-    T: Clone + Deref + Eq + Hash + Ord,
+    T: AsRef<Path> + Clone + Deref + Eq + Hash + Ord,
     F: Fn(&<T as Deref>::Target) -> Result<Vec<T>>,
 {
-    let mut all_precs = targets
+    let all_precs = targets
         .iter()
         .map(|t| get_precs(t).map(|precs| (t.clone(), precs)))
-        .collect::<Result<Vec<_>>>()?;
+        .collect::<Result<HashMap<_, _>>>()?;
 
     let mut topo = TopologicalSort::new();
-    while let Some((target, precs)) = all_precs.pop() {
+    for (target, precs) in &all_precs {
         _ = topo.insert(target.clone());
         for prec in precs {
-            topo.add_dependency(prec, target.clone());
+            topo.add_dependency(prec.clone(), target.clone());
         }
     }
 
     let mut order = Vec::new();
     while !topo.is_empty() {
         let mut v = topo.pop_all();
+        if v.is_empty() {
+            let cycle = find_cycle(&all_precs, &order.iter().collect::<HashSet<_>>());
+            bail!(
+                "Dependency cycle detected: {}",
+                cycle
+                    .iter()
+                    .map(|t| describe(t))
+                    .collect::<Vec<_>>()
+                    .join(" -> ")
+            );
+        }
         v.sort();
         order.extend(v);
     }
@@ -52,6 +66,53 @@ where
     Ok(order)
 }
 
+// Every target not yet ordered has a predecessor not yet ordered, so following
+// predecessors must eventually revisit a target
+fn find_cycle<T>(all_precs: &HashMap<T, Vec<T>>, ordered: &HashSet<&T>) -> Vec<T>
+where
+    T: Clone + Eq + Hash + Ord,
+{
+    let unordered_precs = |target: &T| {
+        let mut precs = all_precs
+            .get(target)
+            .map(|precs| {
+                precs
+                    .iter()
+                    .filter(|p| !ordered.contains(p))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        precs.sort();
+        precs
+    };
+
+    let mut path = Vec::new();
+    let mut current = all_precs
+        .keys()
+        .filter(|t| !ordered.contains(t))
+        .min()
+        .expect("at least one target must be unordered");
+    loop {
+        if let Some(i) = path.iter().position(|t| *t == current) {
+            let mut cycle = path.split_off(i).into_iter().cloned().collect::<Vec<_>>();
+            cycle.push(current.clone());
+            return cycle;
+        }
+        path.push(current);
+        current = unordered_precs(current)
+            .first()
+            .copied()
+            .expect("unordered target must have an unordered predecessor");
+    }
+}
+
+fn describe<T>(target: &T) -> String
+where
+    T: AsRef<Path>,
+{
+    Plan::get_project_name(target.as_ref())
+}
+
 #[cfg(test)]
 mod tests {
     use super::compute_topo_order;
@@ -94,6 +155,16 @@ mod tests {
         Ok(vec![])
     }
 
+    #[allow(clippy::unnecessary_wraps)]
+    fn get_cyclic_precs(target: &Path) -> Result<Vec<PathBuf>> {
+        Ok(match target.to_str() {
+            Some("/ws/aaa") => vec![PathBuf::from("/ws/bbb")],
+            Some("/ws/bbb") => vec![PathBuf::from("/ws/ccc"), PathBuf::from("/ws/ddd")],
+            Some("/ws/ccc") => vec![PathBuf::from("/ws/aaa")],
+            _ => vec![],
+        })
+    }
+
     #[test]
     fn test_paths() -> Result<()> {
         let targets = vec![
@@ -143,4 +214,20 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn test_cycle() {
+        let targets = vec![
+            PathBuf::from("/ws/aaa"),
+            PathBuf::from("/ws/bbb"),
+            PathBuf::from("/ws/ccc"),
+            PathBuf::from("/ws/ddd"),
+        ];
+
+        let e = compute_topo_order(&targets, get_cyclic_precs).expect_err("must fail");
+        assert_eq!(
+            "Dependency cycle detected: aaa -> bbb -> ccc -> aaa",
+            e.to_string()
+        );
+    }
 }